@@ -0,0 +1,96 @@
+//! Session code approving a pool and staking into it in a single deploy.
+//!
+//! The pool's package is approved on the pool's token for exactly the part of `amount` that
+//! `preview_stake` says fits under the cap, so no allowance is left behind once `stake` pulled the
+//! tokens. The amount the pool credited is stored under the `staked_amount` named key of the
+//! calling account.
+//!
+//! The approve call follows the pool's `asset_kind`: CEP-18 and ERC-20 tokens revert on failure,
+//! while wrapped CSPR returns `Result<(), u32>`. Native pools take motes from a purse, have
+//! nothing to approve and are rejected.
+//!
+//! ```bash
+//! casper-client put-deploy \
+//!     --chain-name casper-test \
+//!     --node-address http://localhost:7777 \
+//!     --secret-key ./keys/secret_key.pem \
+//!     --session-path ./target/wasm32-unknown-unknown/release/approve-and-stake.wasm \
+//!     --payment-amount 5000000000 \
+//!     --session-arg "pool_package:contract_package_hash='contract-package-wasm<hex>'" \
+//!     --session-arg "amount:u256='1000'" \
+//!     --session-arg "proof:byte_list...='[]'" \
+//!     --session-arg "allocation:u256='0'"
+//! ```
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, ApiError, ContractHash, ContractPackageHash, Key, RuntimeArgs, U256,
+};
+
+/// Named key of the caller holding the amount credited by the last approve-and-stake.
+const STAKED_AMOUNT: &str = "staked_amount";
+
+/// `asset_kind` values of the pool, as in `cep47::asset::AssetKind`.
+const CEP18: u8 = 0;
+const WRAPPED_CSPR: u8 = 1;
+const ERC20: u8 = 2;
+
+/// Reverted with when the pool's asset kind has no approve to call, such as native CSPR.
+const UNSUPPORTED_ASSET_KIND: ApiError = ApiError::User(1);
+/// Reverted with when wrapped CSPR returns an error from `approve`.
+const APPROVE_FAILED: ApiError = ApiError::User(2);
+
+#[no_mangle]
+fn call() {
+    let pool_package = runtime::get_named_arg::<ContractPackageHash>("pool_package");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let proof = runtime::get_named_arg::<Vec<[u8; 32]>>("proof");
+    let allocation = runtime::get_named_arg::<U256>("allocation");
+
+    let asset_kind: u8 =
+        runtime::call_versioned_contract(pool_package, None, "asset_kind", RuntimeArgs::new());
+    if !matches!(asset_kind, CEP18 | WRAPPED_CSPR | ERC20) {
+        runtime::revert(UNSUPPORTED_ASSET_KIND);
+    }
+    let token: Option<ContractHash> =
+        runtime::call_versioned_contract(pool_package, None, "address", RuntimeArgs::new());
+    let token = token.unwrap_or_revert_with(UNSUPPORTED_ASSET_KIND);
+
+    let (accepted, _): (U256, U256) = runtime::call_versioned_contract(
+        pool_package,
+        None,
+        "preview_stake",
+        runtime_args! { "amount" => amount },
+    );
+
+    let approve_args = runtime_args! {
+        "spender" => Key::Hash(pool_package.value()),
+        "amount" => accepted
+    };
+    if asset_kind == WRAPPED_CSPR {
+        let approved: Result<(), u32> = runtime::call_contract(token, "approve", approve_args);
+        approved.unwrap_or_revert_with(APPROVE_FAILED);
+    } else {
+        let _: () = runtime::call_contract(token, "approve", approve_args);
+    }
+    let staked: U256 = runtime::call_versioned_contract(
+        pool_package,
+        None,
+        "stake",
+        runtime_args! {
+            "amount" => accepted,
+            "proof" => proof,
+            "allocation" => allocation
+        },
+    );
+
+    runtime::put_key(STAKED_AMOUNT, storage::new_uref(staked).into());
+}
@@ -0,0 +1,190 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, ContractPackageHash, Key,
+    RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, pool_args, with_arg, StakingEnv};
+
+const WASM: &str = "approve-and-stake.wasm";
+const STAKED: u64 = 1_000;
+const WRAPPED_CSPR: u8 = 1;
+const UNSUPPORTED_ASSET_KIND: u16 = 1;
+
+fn deploy(token: bool) -> (StakingEnv, Option<ContractHash>, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = if token {
+        Some(env.install_token())
+    } else {
+        None
+    };
+    let pool = env.install_pool(token, U256::from(STAKED * 10));
+    if let Some(token) = token {
+        env.call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "mint",
+            runtime_args! { "owner" => Key::Account(staker), "amount" => U256::from(STAKED) },
+            0,
+        );
+    }
+    (env, token, pool, staker)
+}
+
+fn pool_package(env: &StakingEnv, pool: ContractHash) -> ContractPackageHash {
+    env.builder
+        .get_contract(pool)
+        .unwrap()
+        .contract_package_hash()
+}
+
+fn session_args(pool_package: ContractPackageHash, amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "pool_package" => pool_package,
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn staked_amount(env: &StakingEnv, staker: AccountHash) -> U256 {
+    env.builder
+        .query(None, Key::Account(staker), &["staked_amount".to_string()])
+        .unwrap()
+        .as_cl_value()
+        .unwrap()
+        .clone()
+        .into_t()
+        .unwrap()
+}
+
+#[test]
+fn test_approve_and_stake_in_one_deploy() {
+    let (mut env, token, pool, staker) = deploy(true);
+    let token = token.unwrap();
+    let package = pool_package(&env, pool);
+    env.run_session(staker, WASM, session_args(package, STAKED), 0);
+
+    assert_eq!(staked_amount(&env, staker), U256::from(STAKED));
+    assert_eq!(
+        env.query::<U256>(pool, "staked_balance"),
+        U256::from(STAKED)
+    );
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+    assert_eq!(
+        env.balance_of(token, env.pool_key(pool)),
+        U256::from(STAKED)
+    );
+}
+
+#[test]
+fn test_no_allowance_is_left_behind() {
+    let (mut env, token, pool, staker) = deploy(true);
+    let token = token.unwrap();
+    let pool_key = env.pool_key(pool);
+    env.call(
+        staker,
+        token,
+        "approve",
+        runtime_args! { "spender" => pool_key, "amount" => U256::from(STAKED * 5) },
+        0,
+    );
+    let package = pool_package(&env, pool);
+    env.run_session(staker, WASM, session_args(package, STAKED / 2), 0);
+
+    assert_eq!(staked_amount(&env, staker), U256::from(STAKED / 2));
+    assert_eq!(
+        env.allowance(token, Key::Account(staker), pool_key),
+        U256::zero()
+    );
+}
+
+#[test]
+fn test_cap_clipped_stake_leaves_no_allowance() {
+    let (mut env, token, pool, staker) = deploy(true);
+    let token = token.unwrap();
+    let filled = STAKED * 10 - STAKED / 2;
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(filled),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(filled));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(filled),
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+        0,
+    );
+    let package = pool_package(&env, pool);
+    env.run_session(staker, WASM, session_args(package, STAKED), 0);
+
+    assert_eq!(staked_amount(&env, staker), U256::from(STAKED / 2));
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED / 2)
+    );
+    assert_eq!(
+        env.allowance(token, Key::Account(staker), env.pool_key(pool)),
+        U256::zero()
+    );
+}
+
+#[test]
+fn test_staked_amount_is_what_the_pool_received() {
+    let (mut env, token, pool, staker) = deploy(true);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token.unwrap(),
+        "set_fee",
+        runtime_args! { "fee_bps" => 100u64 },
+        0,
+    );
+    let package = pool_package(&env, pool);
+    env.run_session(staker, WASM, session_args(package, STAKED), 0);
+
+    assert_eq!(
+        staked_amount(&env, staker),
+        U256::from(STAKED - STAKED / 100)
+    );
+}
+
+#[test]
+fn test_wrapped_cspr_pool_is_approved_through_its_interface() {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install(
+        *DEFAULT_ACCOUNT_ADDR,
+        "mock-wcspr.wasm",
+        "staked_token",
+        runtime_args! { "contract_name" => "staked_token" },
+    );
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "asset_kind", WRAPPED_CSPR));
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    let package = pool_package(&env, pool);
+    env.run_session(staker, WASM, session_args(package, STAKED), 0);
+
+    assert_eq!(staked_amount(&env, staker), U256::from(STAKED));
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+    assert_eq!(
+        env.allowance(token, Key::Account(staker), env.pool_key(pool)),
+        U256::zero()
+    );
+}
+
+#[test]
+fn test_native_pool_is_rejected() {
+    let (mut env, _, pool, staker) = deploy(false);
+    let package = pool_package(&env, pool);
+    let error = env.run_session_expect_revert(staker, WASM, session_args(package, STAKED), 0);
+    assert_eq!(error, ApiError::User(UNSUPPORTED_ASSET_KIND));
+}
@@ -0,0 +1,82 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS};
+
+const STAKED: u64 = 1_000;
+const WRAPPED_CSPR: u8 = 1;
+const ERC20: u8 = 2;
+const TRANSFER_FAILED: u16 = 26;
+
+/// Installs `wasm` as the staked token and a pool of `asset_kind` over it.
+fn deploy(wasm: &str, asset_kind: u8) -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install(
+        *DEFAULT_ACCOUNT_ADDR,
+        wasm,
+        "staked_token",
+        runtime_args! { "contract_name" => "staked_token" },
+    );
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "asset_kind", asset_kind));
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    env.approve(token, staker, pool, U256::from(STAKED));
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn assert_round_trip(wasm: &str, asset_kind: u8) {
+    let (mut env, token, pool, staker) = deploy(wasm, asset_kind);
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(
+        env.balance_of(token, env.pool_key(pool)),
+        U256::from(STAKED)
+    );
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED)
+    );
+}
+
+#[test]
+fn test_wrapped_cspr_stake_and_withdraw() {
+    assert_round_trip("mock-wcspr.wasm", WRAPPED_CSPR);
+}
+
+#[test]
+fn test_wrapped_cspr_failed_transfer_reverts() {
+    let (mut env, token, pool, staker) = deploy("mock-wcspr.wasm", WRAPPED_CSPR);
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(STAKED + 1), 0);
+    assert_eq!(error, ApiError::User(TRANSFER_FAILED));
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED)
+    );
+}
+
+/// Legacy ERC-20 ports share the CEP-18 entry points, so the CEP-18 mock stands in for them.
+#[test]
+fn test_erc20_stake_and_withdraw() {
+    assert_round_trip("mock-token.wasm", ERC20);
+}
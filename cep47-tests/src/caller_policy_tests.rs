@@ -0,0 +1,248 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, ContractPackageHash, Key,
+    RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS, WITHDRAW_STARTS,
+};
+
+const STAKED: u64 = 1_000;
+const ACCOUNTS_ONLY: u8 = 0;
+const ALLOWLISTED_CONTRACTS: u8 = 1;
+const ANYONE: u8 = 2;
+const CALLER_NOT_ALLOWED: u16 = 28;
+
+struct Setup {
+    env: StakingEnv,
+    token: ContractHash,
+    pool: ContractHash,
+    caller: ContractHash,
+    caller_package: ContractPackageHash,
+    staker: AccountHash,
+}
+
+fn setup(caller_policy: u8) -> Setup {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "caller_policy", caller_policy));
+    let caller = env.install(
+        *DEFAULT_ACCOUNT_ADDR,
+        "mock-caller.wasm",
+        "caller",
+        runtime_args! { "contract_name" => "caller" },
+    );
+    let caller_package = env.builder.get_contract(caller).unwrap().contract_package_hash();
+    for owner in [Key::Account(staker), Key::from(caller_package)] {
+        env.call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "mint",
+            runtime_args! { "owner" => owner, "amount" => U256::from(STAKED) },
+            0,
+        );
+    }
+    Setup {
+        env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+    }
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+/// Arguments of the mock caller, which approves the pool on `token` before staking.
+fn proxy_args(env: &StakingEnv, token: ContractHash, pool: ContractHash) -> RuntimeArgs {
+    runtime_args! {
+        "pool" => pool,
+        "amount" => U256::from(STAKED),
+        "token" => token,
+        "spender" => env.pool_key(pool)
+    }
+}
+
+#[test]
+fn test_session_caller_is_credited_to_account() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        ..
+    } = setup(ACCOUNTS_ONLY);
+    env.approve(token, staker, pool, U256::from(STAKED));
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+    let staked: U256 = env.view(
+        pool,
+        "amount_staked",
+        runtime_args! { "staker" => Key::Account(staker) },
+        0,
+    );
+    assert_eq!(staked, U256::from(STAKED));
+}
+
+#[test]
+fn test_stored_session_caller_is_credited_to_account() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+    } = setup(ACCOUNTS_ONLY);
+    let args = proxy_args(&env, token, pool);
+    env.call(staker, caller, "stake_as_session", args, 0);
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+    assert_eq!(env.balance_of(token, Key::from(caller_package)), U256::from(STAKED));
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::from(STAKED));
+}
+
+#[test]
+fn test_accounts_only_rejects_contract_caller() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        staker,
+        ..
+    } = setup(ACCOUNTS_ONLY);
+    let args = proxy_args(&env, token, pool);
+    let error = env.call_expect_revert(staker, caller, "stake_as_contract", args, 0);
+    assert_eq!(error, ApiError::User(CALLER_NOT_ALLOWED));
+}
+
+#[test]
+fn test_allowlisted_contract_caller_is_credited_to_package() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+    } = setup(ALLOWLISTED_CONTRACTS);
+    let args = proxy_args(&env, token, pool);
+    let error = env.call_expect_revert(staker, caller, "stake_as_contract", args, 0);
+    assert_eq!(error, ApiError::User(CALLER_NOT_ALLOWED));
+
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_allowed_contract",
+        runtime_args! { "contract_package_hash" => caller_package, "allowed" => true },
+        0,
+    );
+    let args = proxy_args(&env, token, pool);
+    env.call(staker, caller, "stake_as_contract", args, 0);
+    assert_eq!(env.balance_of(token, Key::from(caller_package)), U256::zero());
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::from(STAKED));
+}
+
+#[test]
+fn test_anyone_accepts_contract_caller() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+    } = setup(ANYONE);
+    let args = proxy_args(&env, token, pool);
+    env.call(staker, caller, "stake_as_contract", args, 0);
+    assert_eq!(env.balance_of(token, Key::from(caller_package)), U256::zero());
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::from(STAKED));
+}
+
+#[test]
+fn test_delisted_contract_can_still_withdraw() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+    } = setup(ALLOWLISTED_CONTRACTS);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_allowed_contract",
+        runtime_args! { "contract_package_hash" => caller_package, "allowed" => true },
+        0,
+    );
+    let args = proxy_args(&env, token, pool);
+    env.call(staker, caller, "stake_as_contract", args, 0);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_allowed_contract",
+        runtime_args! { "contract_package_hash" => caller_package, "allowed" => false },
+        0,
+    );
+    env.call(
+        staker,
+        caller,
+        "withdraw_as_contract",
+        proxy_args(&env, token, pool),
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::from(caller_package)),
+        U256::from(STAKED)
+    );
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::zero());
+}
+
+#[test]
+fn test_delisted_contract_cannot_compound() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        caller,
+        caller_package,
+        staker,
+        ..
+    } = setup(ALLOWLISTED_CONTRACTS);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_allowed_contract",
+        runtime_args! { "contract_package_hash" => caller_package, "allowed" => true },
+        0,
+    );
+    let args = proxy_args(&env, token, pool);
+    env.call(staker, caller, "stake_as_contract", args, 0);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_allowed_contract",
+        runtime_args! { "contract_package_hash" => caller_package, "allowed" => false },
+        0,
+    );
+    let error = env.call_expect_revert(
+        staker,
+        caller,
+        "compound_as_contract",
+        proxy_args(&env, token, pool),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(CALLER_NOT_ALLOWED));
+}
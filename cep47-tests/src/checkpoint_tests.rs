@@ -0,0 +1,103 @@
+use casper_types::{account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+use crate::staking_instance::{account, StakingEnv, WITHDRAW_ENDS};
+
+const STAKED: u64 = 1_000;
+
+fn deploy() -> (StakingEnv, ContractHash, AccountHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    let (_, staker) = account(4);
+    let (_, other) = account(5);
+    for owner in [staker, other] {
+        env.fund(owner);
+        env.mint(token, Key::Account(owner), U256::from(STAKED * 2));
+        env.approve(token, owner, pool, U256::from(STAKED * 2));
+    }
+    (env, pool, staker, other)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn staked_at(
+    env: &mut StakingEnv,
+    pool: ContractHash,
+    staker: AccountHash,
+    timestamp: u64,
+) -> U256 {
+    env.view(
+        pool,
+        "staked_at",
+        runtime_args! { "staker" => Key::Account(staker), "timestamp" => timestamp },
+        WITHDRAW_ENDS,
+    )
+}
+
+fn total_staked_at(env: &mut StakingEnv, pool: ContractHash, timestamp: u64) -> U256 {
+    env.view(
+        pool,
+        "total_staked_at",
+        runtime_args! { "timestamp" => timestamp },
+        WITHDRAW_ENDS,
+    )
+}
+
+#[test]
+fn test_staked_at_returns_amount_at_each_time() {
+    let (mut env, pool, staker, _) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+    env.call(staker, pool, "stake", stake_args(STAKED / 2), 20);
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED / 4) },
+        WITHDRAW_ENDS,
+    );
+
+    for (timestamp, amount) in [
+        (0, 0),
+        (9, 0),
+        (10, STAKED),
+        (19, STAKED),
+        (20, STAKED * 3 / 2),
+        (WITHDRAW_ENDS - 1, STAKED * 3 / 2),
+        (WITHDRAW_ENDS, STAKED * 5 / 4),
+        (u64::MAX, STAKED * 5 / 4),
+    ] {
+        assert_eq!(
+            staked_at(&mut env, pool, staker, timestamp),
+            U256::from(amount),
+            "at {}",
+            timestamp
+        );
+    }
+}
+
+#[test]
+fn test_total_staked_at_sums_stakers() {
+    let (mut env, pool, staker, other) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+    env.call(other, pool, "stake", stake_args(STAKED * 2), 20);
+
+    assert_eq!(total_staked_at(&mut env, pool, 9), U256::zero());
+    assert_eq!(total_staked_at(&mut env, pool, 10), U256::from(STAKED));
+    assert_eq!(total_staked_at(&mut env, pool, 20), U256::from(STAKED * 3));
+    assert_eq!(staked_at(&mut env, pool, other, 19), U256::zero());
+}
+
+#[test]
+fn test_stakes_in_same_block_keep_last_amount() {
+    let (mut env, pool, staker, _) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED / 2), 10);
+    env.call(staker, pool, "stake", stake_args(STAKED / 2), 10);
+    assert_eq!(staked_at(&mut env, pool, staker, 10), U256::from(STAKED));
+    assert_eq!(total_staked_at(&mut env, pool, 10), U256::from(STAKED));
+}
@@ -0,0 +1,135 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_STARTS};
+
+const STAKED: u64 = 4_000;
+const REWARD: u64 = 2_000;
+const EXPECTED_WITHDRAW_PHASE: u16 = 9;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 2));
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    env.approve(token, staker, pool, U256::from(STAKED));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(REWARD),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(REWARD));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(REWARD)
+        },
+        0,
+    );
+    env.call(
+        staker,
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(STAKED),
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+        0,
+    );
+    (env, token, pool, staker)
+}
+
+#[test]
+fn test_claim_pays_accrued_reward_once() {
+    let (mut env, token, pool, staker) = deploy();
+    // Halfway from `staking_ends` to `withdraw_ends`, half of the reward has accrued.
+    env.call(
+        staker,
+        pool,
+        "claim_reward",
+        RuntimeArgs::new(),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(REWARD / 2)
+    );
+    env.call(
+        staker,
+        pool,
+        "claim_reward",
+        RuntimeArgs::new(),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(REWARD / 2)
+    );
+    let staked: U256 = env.view(
+        pool,
+        "amount_staked",
+        runtime_args! { "staker" => Key::Account(staker) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(staked, U256::from(STAKED));
+}
+
+#[test]
+fn test_withdraw_after_claim_pays_the_rest() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(
+        staker,
+        pool,
+        "claim_reward",
+        RuntimeArgs::new(),
+        WITHDRAW_STARTS,
+    );
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED + REWARD)
+    );
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_claim_without_stake_pays_nothing() {
+    let (mut env, token, pool, _) = deploy();
+    let (_, stranger) = account(5);
+    env.fund(stranger);
+    env.call(
+        stranger,
+        pool,
+        "claim_reward",
+        RuntimeArgs::new(),
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(env.balance_of(token, Key::Account(stranger)), U256::zero());
+}
+
+#[test]
+fn test_claim_before_withdraw_phase_reverts() {
+    let (mut env, _, pool, staker) = deploy();
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "claim_reward",
+        RuntimeArgs::new(),
+        STAKING_ENDS,
+    );
+    assert_eq!(error, ApiError::User(EXPECTED_WITHDRAW_PHASE));
+}
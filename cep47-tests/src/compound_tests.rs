@@ -0,0 +1,239 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, merkle_leaf, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_STARTS,
+};
+
+const STAKED: u64 = 4_000;
+const REWARD: u64 = 2_000;
+/// Accrued on the whole stake at `WITHDRAW_STARTS`, halfway through the early window.
+const ACCRUED: u64 = REWARD / 2;
+const EXPECTED_WITHDRAW_PHASE: u16 = 9;
+const ALLOCATION_EXCEEDED: u16 = 13;
+const ABOVE_MAXIMUM_STAKE: u16 = 15;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 2));
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    env.approve(token, staker, pool, U256::from(STAKED));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(REWARD),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(REWARD));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(REWARD)
+        },
+        0,
+    );
+    env.call(
+        staker,
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(STAKED),
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+        0,
+    );
+    (env, token, pool, staker)
+}
+
+fn compound_args(proof: Vec<[u8; 32]>, allocation: u64) -> RuntimeArgs {
+    runtime_args! { "proof" => proof, "allocation" => U256::from(allocation) }
+}
+
+#[test]
+fn test_compound_restakes_accrued_reward() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(
+        staker,
+        pool,
+        "compound",
+        compound_args(Vec::new(), 0),
+        WITHDRAW_STARTS,
+    );
+    let staked: U256 = env.view(
+        pool,
+        "amount_staked",
+        runtime_args! { "staker" => Key::Account(staker) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(staked, U256::from(STAKED + ACCRUED));
+    assert_eq!(
+        env.query::<U256>(pool, "staked_balance"),
+        U256::from(STAKED + ACCRUED)
+    );
+    // Only deposits made while staking share the early withdraw reward.
+    assert_eq!(env.query::<U256>(pool, "staked_total"), U256::from(STAKED));
+    assert_eq!(
+        env.query::<U256>(pool, "compounded_total"),
+        U256::from(ACCRUED)
+    );
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED + ACCRUED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED + REWARD)
+    );
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_compound_respects_allocation() {
+    let (mut env, _, pool, staker) = deploy();
+    let allocation = STAKED + ACCRUED / 2;
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_merkle_root",
+        runtime_args! { "merkle_root" => Some(merkle_leaf(staker, allocation)) },
+        STAKING_ENDS,
+    );
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "compound",
+        compound_args(Vec::new(), allocation),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(ALLOCATION_EXCEEDED));
+}
+
+#[test]
+fn test_compound_respects_stake_limits() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_stake_limits",
+        runtime_args! {
+            "min_stake" => U256::from(2),
+            "max_stake_per_address" => U256::from(STAKED + ACCRUED / 2),
+            "max_stakers" => 0u64
+        },
+        STAKING_ENDS,
+    );
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "compound",
+        compound_args(Vec::new(), 0),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(ABOVE_MAXIMUM_STAKE));
+}
+
+#[test]
+fn test_compound_before_withdraw_phase_reverts() {
+    let (mut env, _, pool, staker) = deploy();
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "compound",
+        compound_args(Vec::new(), 0),
+        STAKING_ENDS,
+    );
+    assert_eq!(error, ApiError::User(EXPECTED_WITHDRAW_PHASE));
+}
+
+#[test]
+fn test_compound_does_not_dilute_other_stakers() {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 4));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(REWARD),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(REWARD));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(REWARD)
+        },
+        0,
+    );
+    let (_, compounder) = account(4);
+    let (_, holder) = account(5);
+    for staker in [compounder, holder] {
+        env.fund(staker);
+        env.mint(token, Key::Account(staker), U256::from(STAKED));
+        env.approve(token, staker, pool, U256::from(STAKED));
+        env.call(
+            staker,
+            pool,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(STAKED),
+                "proof" => Vec::<[u8; 32]>::new(),
+                "allocation" => U256::zero()
+            },
+            0,
+        );
+    }
+    // Halfway through the early window each stake has accrued a quarter of the reward.
+    env.call(
+        compounder,
+        pool,
+        "compound",
+        compound_args(Vec::new(), 0),
+        WITHDRAW_STARTS,
+    );
+    let staked: U256 = env.view(
+        pool,
+        "amount_staked",
+        runtime_args! { "staker" => Key::Account(compounder) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(staked, U256::from(STAKED + REWARD / 4));
+
+    env.call(
+        holder,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(holder)),
+        U256::from(STAKED + REWARD / 2)
+    );
+    env.call(
+        compounder,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED + REWARD / 4) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(compounder)),
+        U256::from(STAKED + REWARD / 2)
+    );
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
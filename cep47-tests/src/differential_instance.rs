@@ -0,0 +1,307 @@
+//! Drives the same operations through the `Staking.sol` model and the pool wasm, stopping at the
+//! first difference in outcome, token balances or pool totals.
+use std::fmt::{self, Display, Formatter};
+
+use casper_engine_test_support::{ExecuteRequestBuilder, DEFAULT_ACCOUNT_ADDR};
+use casper_execution_engine::core::engine_state;
+use casper_types::{account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+use crate::{
+    rng::Rng,
+    solidity_model::{Accounting, Erc20, Staking},
+    staking_instance::{
+        account, pool_args, with_arg, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_STARTS,
+    },
+};
+
+pub const STAKER_COUNT: usize = 3;
+pub const INITIAL_TOKENS: u64 = 10_000_000;
+/// `Staking.sol` rejects a zero start.
+const STAKING_STARTS: u64 = 1;
+/// `Staking.sol` only takes amounts above 2.
+const MIN_STAKE: u64 = 3;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
+    Stake {
+        staker: usize,
+        amount: U256,
+    },
+    AddReward {
+        reward_amount: U256,
+        withdrawable_amount: U256,
+    },
+    Withdraw {
+        staker: usize,
+        amount: U256,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Step {
+    /// Block time, `now` to the model.
+    pub time: u64,
+    pub op: Op,
+}
+
+/// The first step where the model and the wasm disagree.
+#[derive(Debug)]
+pub struct Divergence {
+    pub index: usize,
+    pub step: Step,
+    pub what: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "step {} {:?}: {}", self.index, self.step, self.what)
+    }
+}
+
+/// Random steps in time order across the whole schedule. Withdrawals are only generated when
+/// `withdrawals` is set.
+pub fn random_steps(rng: &mut Rng, count: usize, withdrawals: bool) -> Vec<Step> {
+    let mut times: Vec<u64> = (0..count)
+        .map(|_| rng.range(0, WITHDRAW_ENDS + 500))
+        .collect();
+    times.sort_unstable();
+    times
+        .into_iter()
+        .map(|time| {
+            let op = match rng.range(0, if withdrawals { 2 } else { 1 }) {
+                0 => Op::Stake {
+                    staker: rng.range(0, STAKER_COUNT as u64 - 1) as usize,
+                    amount: U256::from(rng.range(0, INITIAL_TOKENS / 5)),
+                },
+                1 => {
+                    let reward_amount = rng.range(0, INITIAL_TOKENS / 5);
+                    Op::AddReward {
+                        reward_amount: U256::from(reward_amount),
+                        withdrawable_amount: U256::from(rng.range(0, reward_amount + 1_000)),
+                    }
+                }
+                _ => Op::Withdraw {
+                    staker: rng.range(0, STAKER_COUNT as u64 - 1) as usize,
+                    amount: U256::from(rng.range(0, INITIAL_TOKENS / 5)),
+                },
+            };
+            Step { time, op }
+        })
+        .collect()
+}
+
+/// The model and a freshly installed pool, with the same token balances.
+pub struct Differential {
+    env: StakingEnv,
+    model: Staking,
+    token: ContractHash,
+    pool: ContractHash,
+    pool_key: Key,
+    stakers: Vec<AccountHash>,
+    funder: AccountHash,
+}
+
+impl Differential {
+    /// Installs a pool capped at `staking_total` next to a model booking stakes per `accounting`.
+    pub fn new(staking_total: U256, accounting: Accounting) -> Differential {
+        let mut env = StakingEnv::new();
+        let token = env.install_token();
+        let args = pool_args(Some(token), staking_total);
+        let args = with_arg(args, "staking_starts", STAKING_STARTS);
+        let args = with_arg(args, "min_stake", U256::from(MIN_STAKE));
+        let pool = env.install_pool_with(args);
+        let pool_key = env.pool_key(pool);
+
+        let stakers: Vec<AccountHash> = (0..STAKER_COUNT)
+            .map(|index| account(10 + index as u8).1)
+            .collect();
+        let funder = *DEFAULT_ACCOUNT_ADDR;
+        let mut model_token = Erc20::default();
+        for owner in stakers.iter().chain([funder].iter()) {
+            if *owner != funder {
+                env.fund(*owner);
+            }
+            let amount = U256::from(INITIAL_TOKENS);
+            env.call(
+                funder,
+                token,
+                "mint",
+                runtime_args! { "owner" => Key::Account(*owner), "amount" => amount },
+                0,
+            );
+            model_token.mint(Key::Account(*owner), amount).unwrap();
+        }
+        let model = Staking::constructor(
+            pool_key,
+            model_token,
+            "token",
+            STAKING_STARTS,
+            STAKING_ENDS,
+            WITHDRAW_STARTS,
+            WITHDRAW_ENDS,
+            staking_total,
+            0,
+            accounting,
+        )
+        .unwrap();
+
+        Differential {
+            env,
+            model,
+            token,
+            pool,
+            pool_key,
+            stakers,
+            funder,
+        }
+    }
+
+    pub fn model(&self) -> &Staking {
+        &self.model
+    }
+
+    /// Runs `steps` through both, returning the first divergence.
+    pub fn run(&mut self, steps: &[Step]) -> Result<(), Divergence> {
+        for (index, step) in steps.iter().enumerate() {
+            self.step(*step).map_err(|what| Divergence {
+                index,
+                step: *step,
+                what,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, step: Step) -> Result<(), String> {
+        let Step { time, op } = step;
+        let (sender, model, entry_point, args) = match op {
+            Op::Stake { staker, amount } => {
+                let sender = self.stakers[staker];
+                // Both pull the stake through an allowance granted beforehand.
+                self.model
+                    .token
+                    .approve(Key::Account(sender), self.pool_key, amount);
+                self.env.approve(self.token, sender, self.pool, amount);
+                let model = self.model.stake(Key::Account(sender), amount, time);
+                let args = runtime_args! {
+                    "amount" => amount,
+                    "proof" => Vec::<[u8; 32]>::new(),
+                    "allocation" => U256::zero()
+                };
+                (sender, model, "stake", args)
+            }
+            Op::AddReward {
+                reward_amount,
+                withdrawable_amount,
+            } => {
+                let sender = self.funder;
+                self.model
+                    .token
+                    .approve(Key::Account(sender), self.pool_key, reward_amount);
+                self.env
+                    .approve(self.token, sender, self.pool, reward_amount);
+                let model = self.model.add_reward(
+                    Key::Account(sender),
+                    reward_amount,
+                    withdrawable_amount,
+                    time,
+                );
+                let args = runtime_args! {
+                    "reward_amount" => reward_amount,
+                    "withdrawable_amount" => withdrawable_amount
+                };
+                (sender, model, "add_reward", args)
+            }
+            Op::Withdraw { staker, amount } => {
+                let sender = self.stakers[staker];
+                let model = self.model.withdraw(Key::Account(sender), amount, time);
+                (
+                    sender,
+                    model,
+                    "withdraw",
+                    runtime_args! { "amount" => amount },
+                )
+            }
+        };
+
+        match (model, self.call(sender, entry_point, args, time)) {
+            (Ok(_), None) | (Err(_), Some(_)) => {}
+            (Err(reason), None) => {
+                return Err(format!(
+                    "Staking.sol reverted with `{}`, the pool succeeded",
+                    reason
+                ))
+            }
+            (Ok(_), Some(error)) => {
+                return Err(format!(
+                    "the pool failed with {:?}, Staking.sol succeeded",
+                    error
+                ))
+            }
+        }
+        self.compare_balances()?;
+        self.compare_totals()
+    }
+
+    /// Calls the pool, returning the error if it failed.
+    fn call(
+        &mut self,
+        sender: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) -> Option<engine_state::Error> {
+        let request =
+            ExecuteRequestBuilder::contract_call_by_hash(sender, self.pool, entry_point, args)
+                .with_block_time(time)
+                .build();
+        self.env.builder.exec(request).commit();
+        self.env.builder.get_error()
+    }
+
+    fn compare_balances(&self) -> Result<(), String> {
+        let owners = self
+            .stakers
+            .iter()
+            .chain([self.funder].iter())
+            .map(|owner| Key::Account(*owner))
+            .chain([self.pool_key].iter().copied());
+        for owner in owners {
+            compare(
+                &format!("token balance of {}", owner.to_formatted_string()),
+                self.model.token.balance_of(owner),
+                self.env.balance_of(self.token, owner),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Compares the totals both keep. `stakedBalance` is only compared when the model books
+    /// stakes into it.
+    fn compare_totals(&self) -> Result<(), String> {
+        for (name, expected) in [
+            ("staked_total", self.model.staked_total),
+            ("total_reward", self.model.total_reward),
+            ("reward_balance", self.model.reward_balance),
+            ("early_withdraw_reward", self.model.early_withdraw_reward),
+        ] {
+            compare(name, expected, self.env.query(self.pool, name))?;
+        }
+        if self.model.accounting() == Accounting::Booked {
+            compare(
+                "staked_balance",
+                self.model.staked_balance,
+                self.env.query(self.pool, "staked_balance"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn compare(what: &str, model: U256, wasm: U256) -> Result<(), String> {
+    if model == wasm {
+        Ok(())
+    } else {
+        Err(format!("{}: Staking.sol {}, pool {}", what, model, wasm))
+    }
+}
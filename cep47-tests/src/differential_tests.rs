@@ -0,0 +1,129 @@
+use std::{env, str::FromStr};
+
+use casper_types::U256;
+
+use crate::{
+    differential_instance::{random_steps, Differential, Op, Step},
+    rng::Rng,
+    solidity_model::Accounting,
+    staking_instance::{WITHDRAW_ENDS, WITHDRAW_STARTS},
+};
+
+const STAKING_TOTAL: u64 = 1_000_000_000_000;
+/// Default number of random runs, and of steps in each. `DIFFERENTIAL_SEEDS` and
+/// `DIFFERENTIAL_STEPS` override them for longer runs.
+const SEEDS: u64 = 10;
+const STEPS: usize = 40;
+
+/// Reads `name` from the environment, falling back to `default` when unset or malformed.
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn stake(time: u64, staker: usize, amount: u64) -> Step {
+    Step {
+        time,
+        op: Op::Stake {
+            staker,
+            amount: U256::from(amount),
+        },
+    }
+}
+
+fn withdraw(time: u64, staker: usize, amount: u64) -> Step {
+    Step {
+        time,
+        op: Op::Withdraw {
+            staker,
+            amount: U256::from(amount),
+        },
+    }
+}
+
+fn add_reward(time: u64, reward_amount: u64, withdrawable_amount: u64) -> Step {
+    Step {
+        time,
+        op: Op::AddReward {
+            reward_amount: U256::from(reward_amount),
+            withdrawable_amount: U256::from(withdrawable_amount),
+        },
+    }
+}
+
+/// Random stakes, funding and withdrawals, against the model booking `stakedBalance` as the pool
+/// does.
+#[test]
+fn test_random_steps_match_booked_solidity() {
+    let steps_per_seed = env_or("DIFFERENTIAL_STEPS", STEPS);
+    for seed in 1..=env_or("DIFFERENTIAL_SEEDS", SEEDS) {
+        let steps = random_steps(&mut Rng::new(seed), steps_per_seed, true);
+        let mut differential = Differential::new(U256::from(STAKING_TOTAL), Accounting::Booked);
+        if let Err(divergence) = differential.run(&steps) {
+            panic!("seed {}: {}", seed, divergence);
+        }
+    }
+}
+
+#[test]
+fn test_early_and_after_close_payouts_match_booked_solidity() {
+    let steps = [
+        stake(10, 0, 3_000),
+        stake(20, 1, 1_000),
+        add_reward(30, 4_000, 2_000),
+        withdraw(WITHDRAW_STARTS, 0, 1_000),
+        withdraw(WITHDRAW_ENDS, 0, 2_000),
+        withdraw(WITHDRAW_ENDS + 1, 1, 1_000),
+    ];
+    Differential::new(U256::from(STAKING_TOTAL), Accounting::Booked)
+        .run(&steps)
+        .unwrap();
+}
+
+/// `Staking.sol` never adds stakes to `stakedBalance`, so subtracting a withdrawal from it
+/// underflows. The pool books stakes and pays out.
+#[test]
+fn test_early_withdraw_diverges_from_solidity() {
+    let steps = [stake(10, 0, 1_000), withdraw(WITHDRAW_STARTS, 0, 1_000)];
+    let divergence = Differential::new(U256::from(STAKING_TOTAL), Accounting::Solidity)
+        .run(&steps)
+        .unwrap_err();
+    assert_eq!(divergence.index, 1);
+    assert!(
+        divergence.what.contains("SafeMath: subtraction overflow"),
+        "{}",
+        divergence
+    );
+}
+
+/// For the same reason, `Staking.sol` divides by a zero `stakedBalance` after close.
+#[test]
+fn test_withdraw_after_close_diverges_from_solidity() {
+    let steps = [stake(10, 0, 1_000), withdraw(WITHDRAW_ENDS, 0, 1_000)];
+    let divergence = Differential::new(U256::from(STAKING_TOTAL), Accounting::Solidity)
+        .run(&steps)
+        .unwrap_err();
+    assert_eq!(divergence.index, 1);
+    assert!(
+        divergence.what.contains("SafeMath: division by zero"),
+        "{}",
+        divergence
+    );
+}
+
+/// `Staking.sol` clips a stake larger than half of `stakingTotal` to `stakingTotal - amount`,
+/// where the pool accepts up to the room left under the cap.
+#[test]
+fn test_cap_clipping_diverges_from_solidity() {
+    let mut differential = Differential::new(U256::from(10_000), Accounting::Solidity);
+    let divergence = differential.run(&[stake(10, 0, 6_000)]).unwrap_err();
+    assert_eq!(divergence.index, 0);
+    assert!(
+        divergence.what.starts_with("token balance"),
+        "{}",
+        divergence
+    );
+    assert_eq!(differential.model().staked_total, U256::from(4_000));
+}
@@ -0,0 +1,119 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, pool_args, with_arg, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS,
+};
+
+const STAKED: u64 = 10_000;
+const REWARD: u64 = 5_000;
+const FEE_BPS: u64 = 100;
+const BELOW_MINIMUM_STAKE: u16 = 14;
+
+fn after_fee(amount: u64) -> U256 {
+    U256::from(amount - amount * FEE_BPS / 10_000)
+}
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    deploy_with_min_stake(U256::from(2))
+}
+
+fn deploy_with_min_stake(min_stake: U256) -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "min_stake", min_stake));
+    for (owner, amount) in [(staker, STAKED), (*DEFAULT_ACCOUNT_ADDR, REWARD)] {
+        env.call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "mint",
+            runtime_args! { "owner" => Key::Account(owner), "amount" => U256::from(amount) },
+            0,
+        );
+        env.approve(token, owner, pool, U256::from(amount));
+    }
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token,
+        "set_fee",
+        runtime_args! { "fee_bps" => FEE_BPS },
+        0,
+    );
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+#[test]
+fn test_stake_credits_amount_received() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), after_fee(STAKED));
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), after_fee(STAKED));
+}
+
+#[test]
+fn test_stake_below_minimum_after_fee_reverts() {
+    let (mut env, token, pool, staker) = deploy_with_min_stake(U256::from(STAKED));
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(error, ApiError::User(BELOW_MINIMUM_STAKE));
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::zero());
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED)
+    );
+}
+
+#[test]
+fn test_add_reward_credits_amount_received() {
+    let (mut env, _, pool, _) = deploy();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(REWARD)
+        },
+        0,
+    );
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), after_fee(REWARD));
+    assert_eq!(env.query::<U256>(pool, "early_withdraw_reward"), after_fee(REWARD));
+}
+
+#[test]
+fn test_pool_stays_solvent_with_fee_token() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::zero()
+        },
+        STAKING_ENDS,
+    );
+    let staked = env.query::<U256>(pool, "staked_balance");
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => staked },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::zero());
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
@@ -0,0 +1,253 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS, WITHDRAW_STARTS,
+};
+
+const STAKED: u64 = 4_000;
+const NOT_FINALIZED: u16 = 30;
+
+struct Setup {
+    env: StakingEnv,
+    token: ContractHash,
+    pool: ContractHash,
+    staker: AccountHash,
+}
+
+fn setup(staking_starts: u64) -> Setup {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED * 2));
+    let pool = env.install_pool_with(with_arg(args, "staking_starts", staking_starts));
+    let (_, staker) = account(4);
+    env.fund(staker);
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    env.approve(token, staker, pool, U256::from(STAKED));
+    Setup {
+        env,
+        token,
+        pool,
+        staker,
+    }
+}
+
+fn add_reward(
+    env: &mut StakingEnv,
+    token: ContractHash,
+    pool: ContractHash,
+    funder: AccountHash,
+    reward_amount: u64,
+    withdrawable_amount: u64,
+) {
+    env.mint(token, Key::Account(funder), U256::from(reward_amount));
+    env.approve(token, funder, pool, U256::from(reward_amount));
+    env.call(
+        funder,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(reward_amount),
+            "withdrawable_amount" => U256::from(withdrawable_amount)
+        },
+        0,
+    );
+}
+
+fn stake(env: &mut StakingEnv, pool: ContractHash, staker: AccountHash, time: u64) {
+    env.call(
+        staker,
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(STAKED),
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+        time,
+    );
+}
+
+fn withdraw(env: &mut StakingEnv, pool: ContractHash, staker: AccountHash, amount: u64, time: u64) {
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(amount) },
+        time,
+    );
+}
+
+#[test]
+fn test_funders_claim_refunds_pro_rata() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+    } = setup(0);
+    let funder = *DEFAULT_ACCOUNT_ADDR;
+    let (_, other_funder) = account(6);
+    env.fund(other_funder);
+    add_reward(&mut env, token, pool, funder, 3_000, 2_000);
+    add_reward(&mut env, token, pool, other_funder, 1_000, 0);
+    stake(&mut env, pool, staker, 0);
+    // Halfway through the early window, the staker takes half of the early withdraw reward.
+    withdraw(&mut env, pool, staker, STAKED, WITHDRAW_STARTS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED + 1_000)
+    );
+
+    env.call(staker, pool, "finalize", RuntimeArgs::new(), WITHDRAW_ENDS);
+    assert_eq!(env.query::<U256>(pool, "refund_pool"), U256::from(3_000));
+    for (owner, refund) in [(funder, 2_250), (other_funder, 750)] {
+        env.call(
+            owner,
+            pool,
+            "claim_refund",
+            RuntimeArgs::new(),
+            WITHDRAW_ENDS,
+        );
+        assert_eq!(
+            env.balance_of(token, Key::Account(owner)),
+            U256::from(refund)
+        );
+        // A second claim pays nothing.
+        env.call(
+            owner,
+            pool,
+            "claim_refund",
+            RuntimeArgs::new(),
+            WITHDRAW_ENDS,
+        );
+        assert_eq!(
+            env.balance_of(token, Key::Account(owner)),
+            U256::from(refund)
+        );
+    }
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_finalize_without_funders_pays_owner() {
+    let staking_starts = 100;
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+    } = setup(staking_starts);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        runtime_args! {
+            "penalty_bps" => 1_000u64,
+            "decays" => false,
+            "treasury" => Option::<Key>::None
+        },
+        0,
+    );
+    stake(&mut env, pool, staker, staking_starts);
+    withdraw(&mut env, pool, staker, STAKED, WITHDRAW_STARTS);
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::from(400));
+
+    env.call(staker, pool, "finalize", RuntimeArgs::new(), WITHDRAW_ENDS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(*DEFAULT_ACCOUNT_ADDR)),
+        U256::from(400)
+    );
+    assert_eq!(env.query::<U256>(pool, "refund_pool"), U256::zero());
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_finalize_reserves_outstanding_stakes() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+    } = setup(0);
+    let funder = *DEFAULT_ACCOUNT_ADDR;
+    add_reward(&mut env, token, pool, funder, 2_000, 0);
+    stake(&mut env, pool, staker, 0);
+
+    env.call(funder, pool, "finalize", RuntimeArgs::new(), WITHDRAW_ENDS);
+    // The whole reward belongs to the stake still in the pool.
+    assert_eq!(env.query::<U256>(pool, "refund_pool"), U256::zero());
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::from(2_000));
+
+    withdraw(&mut env, pool, staker, STAKED, WITHDRAW_ENDS + 1);
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED + 2_000)
+    );
+    env.call(
+        funder,
+        pool,
+        "claim_refund",
+        RuntimeArgs::new(),
+        WITHDRAW_ENDS + 1,
+    );
+    assert_eq!(env.balance_of(token, Key::Account(funder)), U256::zero());
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_last_funder_takes_refund_remainder() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+    } = setup(0);
+    let funders = [*DEFAULT_ACCOUNT_ADDR, account(6).1, account(7).1];
+    env.fund(funders[1]);
+    env.fund(funders[2]);
+    add_reward(&mut env, token, pool, funders[0], 1_000, 1_000);
+    add_reward(&mut env, token, pool, funders[1], 1_000, 0);
+    add_reward(&mut env, token, pool, funders[2], 1_000, 0);
+    stake(&mut env, pool, staker, 0);
+    withdraw(&mut env, pool, staker, STAKED, WITHDRAW_STARTS);
+
+    env.call(staker, pool, "finalize", RuntimeArgs::new(), WITHDRAW_ENDS);
+    // 2_500 doesn't split evenly in three, the last claimant takes the remainder.
+    assert_eq!(env.query::<U256>(pool, "refund_pool"), U256::from(2_500));
+    for (owner, refund) in funders.iter().zip([833, 833, 834]) {
+        env.call(
+            *owner,
+            pool,
+            "claim_refund",
+            RuntimeArgs::new(),
+            WITHDRAW_ENDS,
+        );
+        assert_eq!(
+            env.balance_of(token, Key::Account(*owner)),
+            U256::from(refund)
+        );
+    }
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::zero());
+}
+
+#[test]
+fn test_claim_refund_before_finalize_reverts() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        ..
+    } = setup(0);
+    add_reward(&mut env, token, pool, *DEFAULT_ACCOUNT_ADDR, 1_000, 0);
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "claim_refund",
+        RuntimeArgs::new(),
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(error, ApiError::User(NOT_FINALIZED));
+}
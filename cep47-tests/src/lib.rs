@@ -3,3 +3,78 @@ pub mod cep47_tests;
 
 #[cfg(test)]
 pub mod cep47_instance;
+
+#[cfg(test)]
+pub mod staking_instance;
+
+#[cfg(test)]
+pub mod reentrancy_tests;
+
+#[cfg(test)]
+pub mod staking_tests;
+
+#[cfg(test)]
+pub mod staker_info_tests;
+
+#[cfg(test)]
+pub mod phase_tests;
+
+#[cfg(test)]
+pub mod preview_tests;
+
+#[cfg(test)]
+pub mod registry_tests;
+
+#[cfg(test)]
+pub mod checkpoint_tests;
+
+#[cfg(test)]
+pub mod merkle_tests;
+
+#[cfg(test)]
+pub mod limits_tests;
+
+#[cfg(test)]
+pub mod penalty_tests;
+
+#[cfg(test)]
+pub mod claim_tests;
+
+#[cfg(test)]
+pub mod compound_tests;
+
+#[cfg(test)]
+pub mod finalize_tests;
+
+#[cfg(test)]
+pub mod recover_tests;
+
+#[cfg(test)]
+pub mod native_tests;
+
+#[cfg(test)]
+pub mod asset_tests;
+
+#[cfg(test)]
+pub mod fee_tests;
+
+#[cfg(test)]
+pub mod caller_policy_tests;
+
+#[cfg(test)]
+pub mod approve_and_stake_tests;
+
+#[cfg(test)]
+pub mod rng;
+
+#[cfg(test)]
+pub mod rewards_tests;
+
+#[cfg(test)]
+pub mod solidity_model;
+
+#[cfg(test)]
+pub mod differential_instance;
+
+#[cfg(test)]
+pub mod differential_tests;
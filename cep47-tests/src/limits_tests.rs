@@ -0,0 +1,173 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, pool_args, with_arg, StakingEnv, WITHDRAW_STARTS};
+
+const STAKING_TOTAL: u64 = 1_000;
+const PERMISSION_DENIED: u16 = 1;
+const NOT_REQUIRED_STAKE: u16 = 3;
+const BELOW_MINIMUM_STAKE: u16 = 14;
+const ABOVE_MAXIMUM_STAKE: u16 = 15;
+const TOO_MANY_STAKERS: u16 = 16;
+
+fn deploy(min_stake: u64) -> (StakingEnv, ContractHash, AccountHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKING_TOTAL));
+    let pool = env.install_pool_with(with_arg(args, "min_stake", U256::from(min_stake)));
+    let (_, staker) = account(4);
+    let (_, other) = account(5);
+    for owner in [staker, other] {
+        env.fund(owner);
+        env.mint(token, Key::Account(owner), U256::from(STAKING_TOTAL));
+        env.approve(token, owner, pool, U256::from(STAKING_TOTAL));
+    }
+    (env, pool, staker, other)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn set_stake_limits(
+    env: &mut StakingEnv,
+    pool: ContractHash,
+    min_stake: u64,
+    max_stake_per_address: u64,
+    max_stakers: u64,
+) {
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_stake_limits",
+        runtime_args! {
+            "min_stake" => U256::from(min_stake),
+            "max_stake_per_address" => U256::from(max_stake_per_address),
+            "max_stakers" => max_stakers
+        },
+        0,
+    );
+}
+
+#[test]
+fn test_stake_below_minimum_reverts() {
+    let (mut env, pool, staker, _) = deploy(100);
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(99), 0);
+    assert_eq!(error, ApiError::User(BELOW_MINIMUM_STAKE));
+    env.call(staker, pool, "stake", stake_args(100), 0);
+}
+
+#[test]
+fn test_zero_stake_reverts_without_minimum() {
+    let (mut env, pool, staker, _) = deploy(0);
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(0), 0);
+    assert_eq!(error, ApiError::User(NOT_REQUIRED_STAKE));
+    let error = env.view_expect_revert(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::zero() },
+        0,
+    );
+    assert_eq!(error, ApiError::User(NOT_REQUIRED_STAKE));
+    let count: u64 = env.view(pool, "staker_count", RuntimeArgs::new(), 0);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_stake_clipped_below_minimum_reverts() {
+    let (mut env, pool, staker, other) = deploy(100);
+    env.call(staker, pool, "stake", stake_args(STAKING_TOTAL - 50), 0);
+    // Only 50 fit under the cap, less than the minimum.
+    let error = env.call_expect_revert(other, pool, "stake", stake_args(500), 0);
+    assert_eq!(error, ApiError::User(BELOW_MINIMUM_STAKE));
+}
+
+#[test]
+fn test_stake_above_maximum_per_address_reverts() {
+    let (mut env, pool, staker, other) = deploy(2);
+    set_stake_limits(&mut env, pool, 2, 500, 0);
+    env.call(staker, pool, "stake", stake_args(400), 0);
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(200), 0);
+    assert_eq!(error, ApiError::User(ABOVE_MAXIMUM_STAKE));
+    env.call(staker, pool, "stake", stake_args(100), 0);
+    env.call(other, pool, "stake", stake_args(500), 0);
+}
+
+#[test]
+fn test_stake_beyond_max_stakers_reverts() {
+    let (mut env, pool, staker, other) = deploy(2);
+    set_stake_limits(&mut env, pool, 2, 0, 1);
+    env.call(staker, pool, "stake", stake_args(100), 0);
+    let error = env.call_expect_revert(other, pool, "stake", stake_args(100), 0);
+    assert_eq!(error, ApiError::User(TOO_MANY_STAKERS));
+    // A registered staker can still add to its stake.
+    env.call(staker, pool, "stake", stake_args(100), 0);
+}
+
+#[test]
+fn test_partial_withdraw_below_minimum_reverts() {
+    let (mut env, pool, staker, _) = deploy(2);
+    env.call(staker, pool, "stake", stake_args(150), 0);
+    set_stake_limits(&mut env, pool, 100, 0, 0);
+    let error = env.view_expect_revert(
+        pool,
+        "preview_withdraw",
+        runtime_args! { "staker" => Key::Account(staker), "amount" => U256::from(99) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(NOT_REQUIRED_STAKE));
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::zero() },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(NOT_REQUIRED_STAKE));
+
+    // The remaining 50 is below the minimum, but it can still leave as the whole stake.
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(100) },
+        WITHDRAW_STARTS,
+    );
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(50) },
+        WITHDRAW_STARTS,
+    );
+    let staked: U256 = env.view(
+        pool,
+        "amount_staked",
+        runtime_args! { "staker" => Key::Account(staker) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(staked, U256::zero());
+}
+
+#[test]
+fn test_only_owner_sets_stake_limits() {
+    let (mut env, pool, staker, _) = deploy(2);
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "set_stake_limits",
+        runtime_args! {
+            "min_stake" => U256::zero(),
+            "max_stake_per_address" => U256::zero(),
+            "max_stakers" => 0u64
+        },
+        0,
+    );
+    assert_eq!(error, ApiError::User(PERMISSION_DENIED));
+}
@@ -0,0 +1,178 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, blake2b, merkle_leaf, StakingEnv};
+
+const ALLOCATION: u64 = 1_000;
+const OTHER_ALLOCATION: u64 = 500;
+const PERMISSION_DENIED: u16 = 1;
+const INVALID_PROOF: u16 = 12;
+const ALLOCATION_EXCEEDED: u16 = 13;
+
+struct Setup {
+    env: StakingEnv,
+    pool: ContractHash,
+    staker: AccountHash,
+    other: AccountHash,
+    /// Proof of `staker` for `ALLOCATION`, the leaf of `other`.
+    proof: Vec<[u8; 32]>,
+}
+
+/// Parent of two nodes, hashed in sorted order.
+fn parent(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    blake2b(&[first, second].concat())
+}
+
+fn setup() -> Setup {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(ALLOCATION * 10));
+    let (_, staker) = account(4);
+    let (_, other) = account(5);
+    for owner in [staker, other] {
+        env.fund(owner);
+        env.mint(token, Key::Account(owner), U256::from(ALLOCATION * 2));
+        env.approve(token, owner, pool, U256::from(ALLOCATION * 2));
+    }
+    let staker_leaf = merkle_leaf(staker, ALLOCATION);
+    let other_leaf = merkle_leaf(other, OTHER_ALLOCATION);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_merkle_root",
+        runtime_args! { "merkle_root" => Some(parent(staker_leaf, other_leaf)) },
+        0,
+    );
+    Setup {
+        env,
+        pool,
+        staker,
+        other,
+        proof: vec![other_leaf],
+    }
+}
+
+fn stake_args(amount: u64, proof: Vec<[u8; 32]>, allocation: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => proof,
+        "allocation" => U256::from(allocation)
+    }
+}
+
+#[test]
+fn test_allowlisted_staker_stakes_up_to_allocation() {
+    let Setup {
+        mut env,
+        pool,
+        staker,
+        proof,
+        ..
+    } = setup();
+    env.call(
+        staker,
+        pool,
+        "stake",
+        stake_args(ALLOCATION / 2, proof.clone(), ALLOCATION),
+        0,
+    );
+    env.call(
+        staker,
+        pool,
+        "stake",
+        stake_args(ALLOCATION / 2, proof.clone(), ALLOCATION),
+        1,
+    );
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(2, proof, ALLOCATION), 2);
+    assert_eq!(error, ApiError::User(ALLOCATION_EXCEEDED));
+    assert_eq!(
+        env.query::<U256>(pool, "staked_balance"),
+        U256::from(ALLOCATION)
+    );
+}
+
+#[test]
+fn test_stake_with_invalid_proof_reverts() {
+    let Setup {
+        mut env,
+        pool,
+        staker,
+        other,
+        proof,
+    } = setup();
+    // A larger allocation than the one committed to.
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "stake",
+        stake_args(ALLOCATION, proof.clone(), ALLOCATION * 2),
+        0,
+    );
+    assert_eq!(error, ApiError::User(INVALID_PROOF));
+    // Someone else's proof.
+    let error = env.call_expect_revert(
+        other,
+        pool,
+        "stake",
+        stake_args(OTHER_ALLOCATION, proof, OTHER_ALLOCATION),
+        0,
+    );
+    assert_eq!(error, ApiError::User(INVALID_PROOF));
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "stake",
+        stake_args(ALLOCATION, Vec::new(), ALLOCATION),
+        0,
+    );
+    assert_eq!(error, ApiError::User(INVALID_PROOF));
+}
+
+#[test]
+fn test_clearing_root_opens_pool() {
+    let Setup {
+        mut env,
+        pool,
+        staker,
+        ..
+    } = setup();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_merkle_root",
+        runtime_args! { "merkle_root" => Option::<[u8; 32]>::None },
+        0,
+    );
+    env.call(
+        staker,
+        pool,
+        "stake",
+        stake_args(ALLOCATION * 2, Vec::new(), 0),
+        0,
+    );
+}
+
+#[test]
+fn test_only_owner_sets_root() {
+    let Setup {
+        mut env,
+        pool,
+        staker,
+        ..
+    } = setup();
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "set_merkle_root",
+        runtime_args! { "merkle_root" => Option::<[u8; 32]>::None },
+        0,
+    );
+    assert_eq!(error, ApiError::User(PERMISSION_DENIED));
+}
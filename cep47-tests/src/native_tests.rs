@@ -0,0 +1,109 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, URef, U256, U512,
+};
+
+use crate::staking_instance::{account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS};
+
+const STAKED: u64 = 500_000_000_000;
+const STAKING_STARTS: u64 = 100;
+const ANYONE: u8 = 2;
+const WRONG_ARGUMENTS: u16 = 2;
+const ACCOUNT_REQUIRED: u16 = 21;
+
+fn deploy(args: RuntimeArgs) -> (StakingEnv, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let pool = env.install_pool_with(args);
+    (env, pool, staker)
+}
+
+fn native_args() -> RuntimeArgs {
+    let args = pool_args(None, U256::from(STAKED * 2));
+    with_arg(args, "staking_starts", STAKING_STARTS)
+}
+
+fn stake_args(purse: Option<URef>) -> RuntimeArgs {
+    let mut args = runtime_args! {
+        "amount" => U256::from(STAKED),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    };
+    if let Some(purse) = purse {
+        args.insert("purse", purse).unwrap();
+    }
+    args
+}
+
+fn pool_purse_balance(env: &StakingEnv, pool: ContractHash) -> U512 {
+    let contract = env.builder.get_contract(pool).unwrap();
+    let purse = contract.named_keys()["pool_purse"].into_uref().unwrap();
+    env.builder.get_purse_balance(purse)
+}
+
+#[test]
+fn test_native_stake_and_withdraw() {
+    let (mut env, pool, staker) = deploy(native_args());
+    let purse = env.builder.get_expected_account(staker).main_purse();
+    env.call(
+        staker,
+        pool,
+        "stake",
+        stake_args(Some(purse)),
+        STAKING_STARTS,
+    );
+    assert_eq!(pool_purse_balance(&env, pool), U512::from(STAKED));
+
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(pool_purse_balance(&env, pool), U512::zero());
+}
+
+#[test]
+fn test_native_stake_without_purse_reverts() {
+    let (mut env, pool, staker) = deploy(native_args());
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(None), STAKING_STARTS);
+    assert_eq!(error, ApiError::User(WRONG_ARGUMENTS));
+}
+
+#[test]
+fn test_native_rejects_contract_staker() {
+    let (mut env, pool, staker) = deploy(with_arg(native_args(), "caller_policy", ANYONE));
+    let caller = env.install(
+        *DEFAULT_ACCOUNT_ADDR,
+        "mock-caller.wasm",
+        "caller",
+        runtime_args! { "contract_name" => "caller" },
+    );
+    let error = env.call_expect_revert(
+        staker,
+        caller,
+        "stake_as_contract",
+        runtime_args! { "pool" => pool, "amount" => U256::from(STAKED) },
+        STAKING_STARTS,
+    );
+    assert_eq!(error, ApiError::User(ACCOUNT_REQUIRED));
+}
+
+#[test]
+fn test_native_rejects_contract_treasury() {
+    let (mut env, pool, _) = deploy(native_args());
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        runtime_args! {
+            "penalty_bps" => 1_000u64,
+            "decays" => false,
+            "treasury" => Some(Key::Hash([7; 32]))
+        },
+        0,
+    );
+    assert_eq!(error, ApiError::User(ACCOUNT_REQUIRED));
+}
@@ -0,0 +1,218 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS, WITHDRAW_STARTS,
+};
+
+const STAKING_STARTS: u64 = 100;
+const STAKED: u64 = 1_000;
+const PERMISSION_DENIED: u16 = 1;
+const WRONG_ARGUMENTS: u16 = 2;
+const EXPECTED_NOT_STARTED_PHASE: u16 = 31;
+
+struct Setup {
+    env: StakingEnv,
+    token: ContractHash,
+    pool: ContractHash,
+    staker: AccountHash,
+    other: AccountHash,
+}
+
+fn setup() -> Setup {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "staking_starts", STAKING_STARTS));
+    let (_, staker) = account(4);
+    let (_, other) = account(5);
+    for owner in [staker, other] {
+        env.fund(owner);
+        env.mint(token, Key::Account(owner), U256::from(STAKED));
+        env.approve(token, owner, pool, U256::from(STAKED));
+    }
+    Setup {
+        env,
+        token,
+        pool,
+        staker,
+        other,
+    }
+}
+
+fn penalty_args(penalty_bps: u64, decays: bool, treasury: Option<Key>) -> RuntimeArgs {
+    runtime_args! {
+        "penalty_bps" => penalty_bps,
+        "decays" => decays,
+        "treasury" => treasury
+    }
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn withdraw_all(env: &mut StakingEnv, pool: ContractHash, staker: AccountHash, time: u64) {
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        time,
+    );
+}
+
+#[test]
+fn test_flat_penalty_goes_to_remaining_stakers() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        other,
+    } = setup();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(1_000, false, None),
+        0,
+    );
+    env.call(staker, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+    env.call(other, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+
+    withdraw_all(&mut env, pool, staker, WITHDRAW_STARTS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED - 100)
+    );
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::from(100));
+
+    withdraw_all(&mut env, pool, other, WITHDRAW_ENDS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(other)),
+        U256::from(STAKED + 100)
+    );
+}
+
+#[test]
+fn test_decaying_penalty_falls_to_zero_at_close() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        other,
+    } = setup();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(2_000, true, None),
+        0,
+    );
+    env.call(staker, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+    env.call(other, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+
+    // Halfway through the early withdraw window, half of the 20% is charged.
+    let halfway = (WITHDRAW_STARTS + WITHDRAW_ENDS) / 2;
+    withdraw_all(&mut env, pool, staker, halfway);
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED - 100)
+    );
+
+    withdraw_all(&mut env, pool, other, WITHDRAW_ENDS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(other)),
+        U256::from(STAKED + 100)
+    );
+}
+
+#[test]
+fn test_penalty_goes_to_treasury() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        ..
+    } = setup();
+    let (_, treasury) = account(6);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(1_000, false, Some(Key::Account(treasury))),
+        0,
+    );
+    env.call(staker, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+
+    withdraw_all(&mut env, pool, staker, WITHDRAW_STARTS);
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED - 100)
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(treasury)),
+        U256::from(100)
+    );
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::zero());
+}
+
+#[test]
+fn test_set_penalty_after_staking_starts_reverts() {
+    let Setup { mut env, pool, .. } = setup();
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(1_000, false, None),
+        STAKING_STARTS,
+    );
+    assert_eq!(error, ApiError::User(EXPECTED_NOT_STARTED_PHASE));
+}
+
+#[test]
+fn test_set_penalty_above_maximum_reverts() {
+    let Setup { mut env, pool, .. } = setup();
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(2_001, false, None),
+        0,
+    );
+    assert_eq!(error, ApiError::User(WRONG_ARGUMENTS));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_penalty",
+        penalty_args(2_000, false, None),
+        0,
+    );
+}
+
+#[test]
+fn test_only_owner_sets_penalty() {
+    let Setup {
+        mut env,
+        pool,
+        staker,
+        ..
+    } = setup();
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "set_penalty",
+        penalty_args(1_000, false, None),
+        0,
+    );
+    assert_eq!(error, ApiError::User(PERMISSION_DENIED));
+}
@@ -0,0 +1,176 @@
+use casper_engine_test_support::{ExecuteRequestBuilder, DEFAULT_ACCOUNT_ADDR};
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{
+    account, pool_args, with_arg, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_STARTS,
+};
+
+const STAKED: u64 = 1_000;
+const STAKING_STARTS: u64 = 100;
+const BAD_TIMING: u16 = 4;
+const EXPECTED_STAKING_PHASE: u16 = 8;
+const EXPECTED_WITHDRAW_PHASE: u16 = 9;
+const EXPECTED_FUNDING_PHASE: u16 = 10;
+
+const NOT_STARTED: u8 = 0;
+const STAKING: u8 = 1;
+const LOCKED: u8 = 2;
+const EARLY_WITHDRAW: u8 = 3;
+const CLOSED: u8 = 4;
+
+fn deploy() -> (StakingEnv, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED * 10));
+    let pool = env.install_pool_with(with_arg(args, "staking_starts", STAKING_STARTS));
+    env.mint(token, Key::Account(staker), U256::from(STAKED * 10));
+    env.approve(token, staker, pool, U256::from(STAKED * 10));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(STAKED),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(STAKED));
+    (env, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn reward_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "reward_amount" => U256::from(amount),
+        "withdrawable_amount" => U256::zero()
+    }
+}
+
+#[test]
+fn test_current_phase_follows_schedule() {
+    let (mut env, pool, _) = deploy();
+    for (time, phase) in [
+        (0, NOT_STARTED),
+        (STAKING_STARTS, STAKING),
+        (STAKING_ENDS - 1, STAKING),
+        (STAKING_ENDS, LOCKED),
+        (WITHDRAW_STARTS, EARLY_WITHDRAW),
+        (WITHDRAW_ENDS - 1, EARLY_WITHDRAW),
+        (WITHDRAW_ENDS, CLOSED),
+    ] {
+        let current: u8 = env.view(pool, "current_phase", RuntimeArgs::new(), time);
+        assert_eq!(current, phase, "at {}", time);
+    }
+}
+
+#[test]
+fn test_stake_only_during_staking() {
+    let (mut env, pool, staker) = deploy();
+    for time in [0, STAKING_ENDS, WITHDRAW_STARTS, WITHDRAW_ENDS] {
+        let error = env.call_expect_revert(staker, pool, "stake", stake_args(STAKED), time);
+        assert_eq!(error, ApiError::User(EXPECTED_STAKING_PHASE), "at {}", time);
+    }
+    env.call(staker, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+}
+
+#[test]
+fn test_withdraw_only_after_withdraw_starts() {
+    let (mut env, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), STAKING_STARTS);
+    for time in [STAKING_STARTS, STAKING_ENDS, WITHDRAW_STARTS - 1] {
+        let error = env.call_expect_revert(
+            staker,
+            pool,
+            "withdraw",
+            runtime_args! { "amount" => U256::from(STAKED) },
+            time,
+        );
+        assert_eq!(
+            error,
+            ApiError::User(EXPECTED_WITHDRAW_PHASE),
+            "at {}",
+            time
+        );
+    }
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_STARTS,
+    );
+}
+
+#[test]
+fn test_add_reward_only_before_withdraw_starts() {
+    let (mut env, pool, _) = deploy();
+    for time in [WITHDRAW_STARTS, WITHDRAW_ENDS] {
+        let error = env.call_expect_revert(
+            *DEFAULT_ACCOUNT_ADDR,
+            pool,
+            "add_reward",
+            reward_args(STAKED),
+            time,
+        );
+        assert_eq!(error, ApiError::User(EXPECTED_FUNDING_PHASE), "at {}", time);
+    }
+    for time in [0, STAKING_STARTS, STAKING_ENDS] {
+        env.call(
+            *DEFAULT_ACCOUNT_ADDR,
+            pool,
+            "add_reward",
+            reward_args(STAKED / 4),
+            time,
+        );
+    }
+    assert_eq!(
+        env.query::<U256>(pool, "reward_balance"),
+        U256::from(STAKED / 4 * 3)
+    );
+}
+
+#[test]
+fn test_install_rejects_unordered_schedule() {
+    for (staking_starts, staking_ends, withdraw_starts, withdraw_ends) in [
+        (STAKING_ENDS, STAKING_ENDS, WITHDRAW_STARTS, WITHDRAW_ENDS),
+        (0, WITHDRAW_STARTS + 1, WITHDRAW_STARTS, WITHDRAW_ENDS),
+        (0, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_ENDS),
+        (0, STAKING_ENDS, WITHDRAW_ENDS, WITHDRAW_STARTS),
+    ] {
+        let mut env = StakingEnv::new();
+        let token = env.install_token();
+        let args = pool_args(Some(token), U256::from(STAKED));
+        let args = with_arg(args, "staking_starts", staking_starts);
+        let args = with_arg(args, "staking_ends", staking_ends);
+        let args = with_arg(args, "withdraw_starts", withdraw_starts);
+        let args = with_arg(args, "withdraw_ends", withdraw_ends);
+        let request =
+            ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, "cep47-token.wasm", args)
+                .build();
+        env.builder.exec(request).expect_failure().commit();
+        let error = format!("{:?}", env.builder.get_error());
+        assert!(
+            error.contains(&format!("User({})", BAD_TIMING)),
+            "{:?}: {}",
+            (staking_starts, staking_ends, withdraw_starts, withdraw_ends),
+            error
+        );
+    }
+}
+
+#[test]
+fn test_install_accepts_withdraw_starting_at_staking_end() {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let args = pool_args(Some(token), U256::from(STAKED));
+    let pool = env.install_pool_with(with_arg(args, "withdraw_starts", STAKING_ENDS));
+    let phase: u8 = env.view(pool, "current_phase", RuntimeArgs::new(), STAKING_ENDS);
+    assert_eq!(phase, EARLY_WITHDRAW);
+}
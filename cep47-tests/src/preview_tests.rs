@@ -0,0 +1,190 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, StakingEnv, STAKING_ENDS, WITHDRAW_STARTS};
+
+const STAKING_TOTAL: u64 = 10_000;
+const REWARD: u64 = 2_000;
+const NOT_REQUIRED_STAKE: u16 = 3;
+const EXPECTED_STAKING_PHASE: u16 = 8;
+const STAKING_CAP_FILLED: u16 = 11;
+const ABOVE_MAXIMUM_STAKE: u16 = 15;
+const INSUFFICIENT_STAKE: u16 = 23;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKING_TOTAL));
+    env.mint(token, Key::Account(staker), U256::from(STAKING_TOTAL));
+    env.approve(token, staker, pool, U256::from(STAKING_TOTAL));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(REWARD),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(REWARD));
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn preview_withdraw_args(staker: AccountHash, amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "staker" => Key::Account(staker),
+        "amount" => U256::from(amount)
+    }
+}
+
+#[test]
+fn test_preview_stake_clips_to_cap() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(9_500), 0);
+
+    let (accepted, refund): (U256, U256) = env.view(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(500) },
+        0,
+    );
+    assert_eq!((accepted, refund), (U256::from(500), U256::zero()));
+    let (accepted, refund): (U256, U256) = env.view(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(400) },
+        0,
+    );
+    assert_eq!((accepted, refund), (U256::from(400), U256::zero()));
+    let preview: (U256, U256) = env.view(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(1_000) },
+        0,
+    );
+    assert_eq!(preview, (U256::from(500), U256::from(500)));
+
+    env.call(staker, pool, "stake", stake_args(1_000), 0);
+    assert_eq!(
+        env.balance_of(token, env.pool_key(pool)),
+        U256::from(STAKING_TOTAL)
+    );
+    assert_eq!(env.balance_of(token, Key::Account(staker)), U256::zero());
+}
+
+#[test]
+fn test_preview_stake_zero_amount_is_not_a_full_cap() {
+    let (mut env, _, pool, staker) = deploy();
+    let error = env.view_expect_revert(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::zero() },
+        0,
+    );
+    assert_eq!(error, ApiError::User(NOT_REQUIRED_STAKE));
+
+    env.call(staker, pool, "stake", stake_args(STAKING_TOTAL), 0);
+    let error = env.view_expect_revert(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(1_000) },
+        0,
+    );
+    assert_eq!(error, ApiError::User(STAKING_CAP_FILLED));
+}
+
+#[test]
+fn test_preview_stake_outside_staking_reverts() {
+    let (mut env, _, pool, _) = deploy();
+    let error = env.view_expect_revert(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(1_000) },
+        STAKING_ENDS,
+    );
+    assert_eq!(error, ApiError::User(EXPECTED_STAKING_PHASE));
+}
+
+#[test]
+fn test_preview_stake_ignores_per_staker_limits() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_stake_limits",
+        runtime_args! {
+            "min_stake" => U256::from(2),
+            "max_stake_per_address" => U256::from(1_000),
+            "max_stakers" => 0u64
+        },
+        0,
+    );
+
+    let preview: (U256, U256) = env.view(
+        pool,
+        "preview_stake",
+        runtime_args! { "amount" => U256::from(2_000) },
+        0,
+    );
+    assert_eq!(preview, (U256::from(2_000), U256::zero()));
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(2_000), 0);
+    assert_eq!(error, ApiError::User(ABOVE_MAXIMUM_STAKE));
+}
+
+#[test]
+fn test_preview_withdraw_matches_withdraw() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(REWARD)
+        },
+        0,
+    );
+    env.call(staker, pool, "stake", stake_args(4_000), 0);
+
+    let (principal, reward, payout): (U256, U256, U256) = env.view(
+        pool,
+        "preview_withdraw",
+        preview_withdraw_args(staker, 1_000),
+        WITHDRAW_STARTS,
+    );
+    // Halfway through the early withdraw window, a quarter of the stake earns half its share.
+    assert_eq!(principal, U256::from(1_000));
+    assert_eq!(reward, U256::from(REWARD / 4 / 2));
+    assert_eq!(payout, principal + reward);
+
+    let before = env.balance_of(token, Key::Account(staker));
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(1_000) },
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(env.balance_of(token, Key::Account(staker)), before + payout);
+}
+
+#[test]
+fn test_preview_withdraw_above_stake_reverts() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(1_000), 0);
+    let error = env.view_expect_revert(
+        pool,
+        "preview_withdraw",
+        preview_withdraw_args(staker, 1_001),
+        WITHDRAW_STARTS,
+    );
+    assert_eq!(error, ApiError::User(INSUFFICIENT_STAKE));
+}
@@ -0,0 +1,200 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, StakingEnv, WITHDRAW_ENDS, WITHDRAW_STARTS};
+
+const STAKED: u64 = 1_000;
+const PERMISSION_DENIED: u16 = 1;
+const EXCEEDS_SURPLUS: u16 = 20;
+
+struct Setup {
+    env: StakingEnv,
+    token: ContractHash,
+    pool: ContractHash,
+    staker: AccountHash,
+    recipient: AccountHash,
+}
+
+fn setup() -> Setup {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    let (_, staker) = account(4);
+    let (_, recipient) = account(5);
+    env.fund(staker);
+    env.mint(token, Key::Account(staker), U256::from(STAKED));
+    env.approve(token, staker, pool, U256::from(STAKED));
+    env.call(
+        staker,
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(STAKED),
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+        0,
+    );
+    Setup {
+        env,
+        token,
+        pool,
+        staker,
+        recipient,
+    }
+}
+
+fn recover_args(token: ContractHash, recipient: AccountHash, amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "token_hash" => token,
+        "recipient" => Key::Account(recipient),
+        "amount" => U256::from(amount)
+    }
+}
+
+#[test]
+fn test_recover_own_token_surplus_only() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        recipient,
+        ..
+    } = setup();
+    let pool_key = env.pool_key(pool);
+    env.mint(token, pool_key, U256::from(500));
+
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "recover_token",
+        recover_args(token, recipient, 501),
+        0,
+    );
+    assert_eq!(error, ApiError::User(EXCEEDS_SURPLUS));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "recover_token",
+        recover_args(token, recipient, 500),
+        0,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(recipient)),
+        U256::from(500)
+    );
+    assert_eq!(env.balance_of(token, pool_key), U256::from(STAKED));
+}
+
+#[test]
+fn test_recover_foreign_token_in_full() {
+    let Setup {
+        mut env,
+        pool,
+        recipient,
+        ..
+    } = setup();
+    let other_token = env.install(
+        *DEFAULT_ACCOUNT_ADDR,
+        "mock-token.wasm",
+        "other_token",
+        runtime_args! { "contract_name" => "other_token" },
+    );
+    let pool_key = env.pool_key(pool);
+    env.mint(other_token, pool_key, U256::from(700));
+
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "recover_token",
+        recover_args(other_token, recipient, 700),
+        0,
+    );
+    assert_eq!(
+        env.balance_of(other_token, Key::Account(recipient)),
+        U256::from(700)
+    );
+    assert_eq!(env.balance_of(other_token, pool_key), U256::zero());
+}
+
+#[test]
+fn test_recover_keeps_unclaimed_refunds() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        recipient,
+    } = setup();
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(STAKED),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(STAKED));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(STAKED),
+            "withdrawable_amount" => U256::zero()
+        },
+        0,
+    );
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_STARTS,
+    );
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "finalize",
+        RuntimeArgs::new(),
+        WITHDRAW_ENDS,
+    );
+
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "recover_token",
+        recover_args(token, recipient, 1),
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(error, ApiError::User(EXCEEDS_SURPLUS));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "claim_refund",
+        RuntimeArgs::new(),
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(*DEFAULT_ACCOUNT_ADDR)),
+        U256::from(STAKED)
+    );
+}
+
+#[test]
+fn test_only_owner_recovers() {
+    let Setup {
+        mut env,
+        token,
+        pool,
+        staker,
+        ..
+    } = setup();
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "recover_token",
+        recover_args(token, staker, 1),
+        0,
+    );
+    assert_eq!(error, ApiError::User(PERMISSION_DENIED));
+}
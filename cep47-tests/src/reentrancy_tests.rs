@@ -0,0 +1,104 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, StakingEnv, STAKING_ENDS, WITHDRAW_ENDS};
+
+const STAKED: u64 = 1_000;
+const REENTRANCY: u16 = 22;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token,
+        "mint",
+        runtime_args! { "owner" => Key::Account(staker), "amount" => U256::from(STAKED * 10) },
+        0,
+    );
+    env.approve(token, staker, pool, U256::from(STAKED * 10));
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn reenter(env: &mut StakingEnv, token: ContractHash, pool: ContractHash, entry_point: &str) {
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token,
+        "set_reentry",
+        runtime_args! { "pool" => Some(pool), "entry_point" => entry_point },
+        0,
+    );
+}
+
+#[test]
+fn test_stake_through_honest_token() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::from(STAKED));
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::from(STAKED));
+}
+
+#[test]
+fn test_token_reentering_stake_reverts() {
+    let (mut env, token, pool, staker) = deploy();
+    reenter(&mut env, token, pool, "stake");
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(STAKED), 0);
+    assert_eq!(error, ApiError::User(REENTRANCY));
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::zero());
+}
+
+#[test]
+fn test_token_reentering_withdraw_reverts() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    reenter(&mut env, token, pool, "withdraw");
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(error, ApiError::User(REENTRANCY));
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::from(STAKED));
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::from(STAKED));
+}
+
+#[test]
+fn test_token_reentering_add_reward_reverts() {
+    let (mut env, token, pool, _) = deploy();
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token,
+        "mint",
+        runtime_args! { "owner" => Key::Account(*DEFAULT_ACCOUNT_ADDR), "amount" => U256::from(STAKED) },
+        0,
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(STAKED));
+    reenter(&mut env, token, pool, "stake");
+    let error = env.call_expect_revert(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(STAKED),
+            "withdrawable_amount" => U256::zero()
+        },
+        STAKING_ENDS,
+    );
+    assert_eq!(error, ApiError::User(REENTRANCY));
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::zero());
+}
@@ -0,0 +1,100 @@
+use casper_types::{account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+use crate::staking_instance::{account, StakingEnv, WITHDRAW_ENDS};
+
+const STAKED: u64 = 1_000;
+
+fn deploy() -> (StakingEnv, ContractHash, Vec<AccountHash>) {
+    let mut env = StakingEnv::new();
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    let stakers: Vec<AccountHash> = (4..7).map(|seed| account(seed).1).collect();
+    for staker in &stakers {
+        env.fund(*staker);
+        env.mint(token, Key::Account(*staker), U256::from(STAKED * 2));
+        env.approve(token, *staker, pool, U256::from(STAKED * 2));
+    }
+    (env, pool, stakers)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn stakers(env: &mut StakingEnv, pool: ContractHash, offset: u64, limit: u64) -> Vec<Key> {
+    env.view(
+        pool,
+        "stakers",
+        runtime_args! { "offset" => offset, "limit" => limit },
+        WITHDRAW_ENDS,
+    )
+}
+
+#[test]
+fn test_registry_lists_each_staker_once() {
+    let (mut env, pool, accounts) = deploy();
+    for staker in &accounts {
+        env.call(*staker, pool, "stake", stake_args(STAKED), 0);
+    }
+    env.call(accounts[0], pool, "stake", stake_args(STAKED), 1);
+
+    let count: u64 = env.view(pool, "staker_count", RuntimeArgs::new(), 1);
+    assert_eq!(count, 3);
+    let expected: Vec<Key> = accounts
+        .iter()
+        .map(|staker| Key::Account(*staker))
+        .collect();
+    assert_eq!(stakers(&mut env, pool, 0, 10), expected);
+}
+
+#[test]
+fn test_stakers_pages_through_registry() {
+    let (mut env, pool, accounts) = deploy();
+    for staker in &accounts {
+        env.call(*staker, pool, "stake", stake_args(STAKED), 0);
+    }
+    assert_eq!(
+        stakers(&mut env, pool, 1, 1),
+        vec![Key::Account(accounts[1])]
+    );
+    assert_eq!(
+        stakers(&mut env, pool, 2, 5),
+        vec![Key::Account(accounts[2])]
+    );
+    assert!(stakers(&mut env, pool, 3, 5).is_empty());
+    assert!(stakers(&mut env, pool, u64::MAX, u64::MAX).is_empty());
+}
+
+#[test]
+fn test_full_withdraw_removes_staker() {
+    let (mut env, pool, accounts) = deploy();
+    for staker in &accounts {
+        env.call(*staker, pool, "stake", stake_args(STAKED), 0);
+    }
+    // A partial withdrawal keeps the staker registered.
+    env.call(
+        accounts[0],
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED / 2) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(stakers(&mut env, pool, 0, 10).len(), 3);
+
+    env.call(
+        accounts[0],
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED / 2) },
+        WITHDRAW_ENDS,
+    );
+    // The last staker moves into the freed slot.
+    assert_eq!(
+        stakers(&mut env, pool, 0, 10),
+        vec![Key::Account(accounts[2]), Key::Account(accounts[1])]
+    );
+}
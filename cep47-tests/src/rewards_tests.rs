@@ -0,0 +1,368 @@
+use casper_types::U256;
+use staking_rewards::{
+    after_close_reward, clip_to_cap, early_exit_penalty, early_withdraw_reward, pro_rata,
+};
+
+use crate::rng::Rng;
+
+const CASES: u64 = 2_000;
+/// Amounts and rewards the module guarantees not to overflow on.
+const AMOUNT_BITS: usize = 96;
+
+struct Schedule {
+    staking_ends: u64,
+    withdraw_ends: u64,
+}
+
+fn schedule(rng: &mut Rng) -> Schedule {
+    let staking_ends = rng.range(0, 1 << 44);
+    let withdraw_ends = staking_ends + rng.range(1, 1 << 40);
+    Schedule {
+        staking_ends,
+        withdraw_ends,
+    }
+}
+
+/// Random nonzero amount below `2^AMOUNT_BITS`, at most `limit`.
+fn amount(rng: &mut Rng, limit: U256) -> U256 {
+    let bits = rng.range(1, AMOUNT_BITS as u64) as usize;
+    let amount = rng.below_pow2(bits);
+    core::cmp::max(U256::one(), amount % (limit + 1))
+}
+
+fn max_amount() -> U256 {
+    (U256::one() << AMOUNT_BITS) - 1
+}
+
+#[test]
+fn test_early_withdraw_reward_grows_with_time() {
+    let mut rng = Rng::new(1);
+    for _ in 0..CASES {
+        let Schedule {
+            staking_ends,
+            withdraw_ends,
+        } = schedule(&mut rng);
+        let staked_total = amount(&mut rng, max_amount());
+        let staked = amount(&mut rng, staked_total);
+        let reward = amount(&mut rng, max_amount());
+        let earlier = rng.range(0, withdraw_ends + 1_000);
+        let later = rng.range(earlier, withdraw_ends + 1_000);
+        let reward_at = |now| {
+            early_withdraw_reward(
+                staked,
+                staked_total,
+                reward,
+                staking_ends,
+                withdraw_ends,
+                now,
+            )
+            .unwrap()
+        };
+        assert!(reward_at(earlier) <= reward_at(later));
+    }
+}
+
+#[test]
+fn test_rewards_grow_with_amount() {
+    let mut rng = Rng::new(2);
+    for _ in 0..CASES {
+        let Schedule {
+            staking_ends,
+            withdraw_ends,
+        } = schedule(&mut rng);
+        let now = rng.range(staking_ends, withdraw_ends);
+        let staked_total = amount(&mut rng, max_amount());
+        let smaller = amount(&mut rng, staked_total);
+        let larger = amount(&mut rng, staked_total).max(smaller);
+        let reward = amount(&mut rng, max_amount());
+        let early = |amount| {
+            early_withdraw_reward(
+                amount,
+                staked_total,
+                reward,
+                staking_ends,
+                withdraw_ends,
+                now,
+            )
+            .unwrap()
+        };
+        assert!(early(smaller) <= early(larger));
+
+        let claimed = amount(&mut rng, max_amount());
+        let after_close =
+            |amount| after_close_reward(amount, staked_total, reward, claimed).unwrap();
+        assert!(after_close(smaller) <= after_close(larger));
+    }
+}
+
+#[test]
+fn test_clip_to_cap_never_exceeds_cap() {
+    let mut rng = Rng::new(3);
+    for _ in 0..CASES {
+        let staking_total = amount(&mut rng, max_amount());
+        let staked_total = amount(&mut rng, staking_total);
+        let smaller = amount(&mut rng, max_amount());
+        let larger = amount(&mut rng, max_amount()).max(smaller);
+
+        let (accepted, refund) = clip_to_cap(larger, staked_total, staking_total);
+        assert_eq!(accepted + refund, larger);
+        assert!(staked_total + accepted <= staking_total);
+        assert!(clip_to_cap(smaller, staked_total, staking_total).0 <= accepted);
+    }
+}
+
+#[test]
+fn test_no_overflow_at_bounds() {
+    let max = max_amount();
+    assert_eq!(
+        early_withdraw_reward(max, max, max, 0, u64::MAX, u64::MAX),
+        Some(max)
+    );
+    assert_eq!(after_close_reward(max, max, max, max), Some(max + max));
+    assert_eq!(
+        early_withdraw_reward(U256::MAX, U256::MAX, U256::MAX, 0, u64::MAX, u64::MAX),
+        None
+    );
+    assert_eq!(
+        after_close_reward(U256::MAX, U256::one(), U256::MAX, U256::one()),
+        None
+    );
+    assert_eq!(
+        clip_to_cap(U256::MAX, U256::MAX, U256::zero()),
+        (U256::zero(), U256::MAX)
+    );
+}
+
+#[test]
+fn test_empty_pool_pays_nothing() {
+    let max = max_amount();
+    assert_eq!(
+        early_withdraw_reward(max, U256::zero(), max, 0, 1_000, 500),
+        Some(U256::zero())
+    );
+    assert_eq!(
+        early_withdraw_reward(max, max, max, 1_000, 2_000, 1_000),
+        Some(U256::zero())
+    );
+    assert_eq!(
+        after_close_reward(max, U256::zero(), max, max),
+        Some(U256::zero())
+    );
+}
+
+/// Staker action in a simulated pool, in the order actions at the same time are played.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Action {
+    Claim,
+    Compound,
+    Withdraw,
+    Exit,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Staker {
+    stake: U256,
+    claimed: U256,
+    compounded: U256,
+}
+
+/// Pool state booked the way `claim_reward`, `compound` and `withdraw` book it, through the same
+/// formulas.
+struct Pool {
+    schedule: Schedule,
+    staking_total: U256,
+    staked_total: U256,
+    early_withdraw_reward: U256,
+    penalty_bps: u64,
+    decays: bool,
+    treasury: bool,
+    reward_balance: U256,
+    staked_balance: U256,
+    compounded_balance: U256,
+    compounded_total: U256,
+    total_claimed: U256,
+    stakers: Vec<Staker>,
+    /// Everything paid out of the pool, principal, rewards and penalties sent to the treasury.
+    paid: U256,
+}
+
+impl Pool {
+    /// Reward on `amount` of the stake of `staker` at `now`, net of what it claimed on it.
+    fn reward(&self, staker: usize, amount: U256, now: u64) -> U256 {
+        let Staker {
+            stake,
+            claimed,
+            compounded,
+        } = self.stakers[staker];
+        let earning = amount - pro_rata(compounded, amount, stake).unwrap();
+        let entitled = if now < self.schedule.withdraw_ends {
+            early_withdraw_reward(
+                earning,
+                self.staked_total,
+                self.early_withdraw_reward,
+                self.schedule.staking_ends,
+                self.schedule.withdraw_ends,
+                now,
+            )
+        } else {
+            after_close_reward(
+                earning,
+                self.staked_balance - self.compounded_balance,
+                self.reward_balance,
+                self.total_claimed,
+            )
+        }
+        .unwrap();
+        entitled.saturating_sub(pro_rata(claimed, amount, stake).unwrap())
+    }
+
+    fn record_claim(&mut self, staker: usize, amount: U256) {
+        self.reward_balance = self
+            .reward_balance
+            .checked_sub(amount)
+            .expect("claim exceeds the reward balance");
+        self.stakers[staker].claimed += amount;
+        self.total_claimed += amount;
+    }
+
+    fn claim(&mut self, staker: usize, now: u64) {
+        let reward = self.reward(staker, self.stakers[staker].stake, now);
+        self.record_claim(staker, reward);
+        self.paid += reward;
+    }
+
+    fn compound(&mut self, staker: usize, now: u64) {
+        let filled = self.staked_total + self.compounded_total;
+        if filled >= self.staking_total {
+            return;
+        }
+        let accrued = self.reward(staker, self.stakers[staker].stake, now);
+        let (amount, _) = clip_to_cap(accrued, filled, self.staking_total);
+        self.record_claim(staker, amount);
+        self.stakers[staker].stake += amount;
+        self.stakers[staker].compounded += amount;
+        self.compounded_total += amount;
+        self.compounded_balance += amount;
+        self.staked_balance += amount;
+    }
+
+    fn withdraw(&mut self, staker: usize, amount: U256, now: u64) {
+        let reward = self.reward(staker, amount, now);
+        let penalty = if now < self.schedule.withdraw_ends {
+            early_exit_penalty(
+                amount,
+                self.penalty_bps,
+                self.decays,
+                self.schedule.staking_ends,
+                self.schedule.withdraw_ends,
+                now,
+            )
+            .unwrap()
+        } else {
+            U256::zero()
+        };
+        let Staker {
+            stake,
+            claimed,
+            compounded,
+        } = self.stakers[staker];
+        let settled_claim = pro_rata(claimed, amount, stake).unwrap();
+        let settled_compound = pro_rata(compounded, amount, stake).unwrap();
+        self.stakers[staker] = Staker {
+            stake: stake - amount,
+            claimed: claimed - settled_claim,
+            compounded: compounded - settled_compound,
+        };
+        self.total_claimed -= settled_claim;
+        self.compounded_balance -= settled_compound;
+        self.reward_balance = self
+            .reward_balance
+            .checked_sub(reward)
+            .expect("reward exceeds the reward balance");
+        self.staked_balance -= amount;
+        if self.treasury {
+            self.paid += penalty;
+        } else {
+            self.reward_balance += penalty;
+        }
+        self.paid += amount - penalty + reward;
+    }
+}
+
+/// Plays random pools to completion and checks that every token paid out was deposited or
+/// funded. Stakers claim, compound and partially withdraw along the way, and leave early in time
+/// order or after close, paying an early exit penalty to the pool or to a treasury.
+#[test]
+fn test_total_paid_never_exceeds_funded() {
+    let mut rng = Rng::new(4);
+    for _ in 0..CASES / 10 {
+        let schedule = schedule(&mut rng);
+        let funded = amount(&mut rng, max_amount());
+        let withdrawable = amount(&mut rng, funded);
+        let stakes: Vec<U256> = (0..rng.range(1, 20))
+            .map(|_| amount(&mut rng, max_amount() / 20))
+            .collect();
+        let staked_total = stakes
+            .iter()
+            .fold(U256::zero(), |total, stake| total + *stake);
+
+        let mut events: Vec<(u64, Action, usize)> = Vec::new();
+        for staker in 0..stakes.len() {
+            let exit = if rng.chance(50) {
+                rng.range(schedule.staking_ends, schedule.withdraw_ends - 1)
+            } else {
+                schedule.withdraw_ends
+            };
+            events.push((exit, Action::Exit, staker));
+            for _ in 0..rng.range(0, 3) {
+                let action = match rng.range(0, 2) {
+                    0 => Action::Claim,
+                    1 => Action::Compound,
+                    _ => Action::Withdraw,
+                };
+                events.push((rng.range(schedule.staking_ends, exit), action, staker));
+            }
+        }
+        events.sort();
+
+        let mut pool = Pool {
+            staking_total: staked_total + amount(&mut rng, max_amount() / 20) - 1,
+            staked_total,
+            early_withdraw_reward: withdrawable,
+            penalty_bps: rng.range(0, 2_000),
+            decays: rng.chance(50),
+            treasury: rng.chance(50),
+            reward_balance: funded,
+            staked_balance: staked_total,
+            compounded_balance: U256::zero(),
+            compounded_total: U256::zero(),
+            total_claimed: U256::zero(),
+            stakers: stakes
+                .iter()
+                .map(|stake| Staker {
+                    stake: *stake,
+                    ..Staker::default()
+                })
+                .collect(),
+            paid: U256::zero(),
+            schedule,
+        };
+        for (now, action, staker) in events {
+            match action {
+                Action::Claim => pool.claim(staker, now),
+                Action::Compound => pool.compound(staker, now),
+                Action::Withdraw => {
+                    let part = amount(&mut rng, pool.stakers[staker].stake) / 2;
+                    if !part.is_zero() {
+                        pool.withdraw(staker, part, now);
+                    }
+                }
+                Action::Exit => pool.withdraw(staker, pool.stakers[staker].stake, now),
+            }
+        }
+        assert_eq!(pool.paid + pool.reward_balance, funded + staked_total);
+        assert!(pool.staked_balance.is_zero());
+        assert!(pool.compounded_balance.is_zero());
+        assert!(pool.total_claimed.is_zero());
+    }
+}
@@ -0,0 +1,43 @@
+//! Seeded xorshift generator driving the randomized tests, so failures reproduce from the seed.
+use casper_types::U256;
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Zero is a fixed point of xorshift.
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `low..=high`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Uniform below `2^bits`.
+    pub fn below_pow2(&mut self, bits: usize) -> U256 {
+        let mut value = U256::zero();
+        for _ in 0..4 {
+            value = (value << 64) | U256::from(self.next_u64());
+        }
+        if bits == 0 {
+            U256::zero()
+        } else {
+            value >> (256 - bits)
+        }
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+}
@@ -0,0 +1,412 @@
+//! In-memory model of `Staking.sol`, following the contract line by line.
+//!
+//! `SafeMath` calls revert with OpenZeppelin's messages while plain Solidity 0.6 arithmetic wraps,
+//! `now` is passed to every call, and the token is OpenZeppelin's `ERC20`. A call that reverts
+//! leaves the model untouched, as a reverted transaction would.
+//!
+//! [`Accounting::Booked`] departs from the contract in one place, the `stakedBalance` bookkeeping,
+//! so that withdrawals can be compared at all.
+use std::collections::BTreeMap;
+
+use casper_types::{Key, U256};
+
+/// The reason string of a Solidity `require` or `SafeMath` failure.
+pub type Revert = &'static str;
+
+trait SafeMath: Sized {
+    fn add(self, other: Self) -> Result<Self, Revert>;
+    fn sub(self, other: Self) -> Result<Self, Revert>;
+    fn mul(self, other: Self) -> Result<Self, Revert>;
+    fn div(self, other: Self) -> Result<Self, Revert>;
+}
+
+impl SafeMath for U256 {
+    fn add(self, other: U256) -> Result<U256, Revert> {
+        self.checked_add(other).ok_or("SafeMath: addition overflow")
+    }
+
+    fn sub(self, other: U256) -> Result<U256, Revert> {
+        self.checked_sub(other)
+            .ok_or("SafeMath: subtraction overflow")
+    }
+
+    fn mul(self, other: U256) -> Result<U256, Revert> {
+        self.checked_mul(other)
+            .ok_or("SafeMath: multiplication overflow")
+    }
+
+    fn div(self, other: U256) -> Result<U256, Revert> {
+        self.checked_div(other).ok_or("SafeMath: division by zero")
+    }
+}
+
+fn require(condition: bool, reason: Revert) -> Result<(), Revert> {
+    if condition {
+        Ok(())
+    } else {
+        Err(reason)
+    }
+}
+
+/// How the model books `stakedBalance`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accounting {
+    /// As `Staking.sol` does, which never adds stakes to `stakedBalance`. Every early withdrawal
+    /// then underflows and every withdrawal after close divides by zero.
+    Solidity,
+    /// As the pool does: a stake is added to `stakedBalance`, and a withdrawal after close takes
+    /// its stake out of `stakedBalance` and its reward out of `rewardBalance`.
+    Booked,
+}
+
+/// OpenZeppelin's `ERC20`, reduced to what `Staking.sol` calls.
+#[derive(Clone, Debug, Default)]
+pub struct Erc20 {
+    balances: BTreeMap<Key, U256>,
+    allowances: BTreeMap<(Key, Key), U256>,
+}
+
+impl Erc20 {
+    pub fn balance_of(&self, account: Key) -> U256 {
+        self.balances.get(&account).copied().unwrap_or_default()
+    }
+
+    pub fn allowance(&self, owner: Key, spender: Key) -> U256 {
+        self.allowances
+            .get(&(owner, spender))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn mint(&mut self, account: Key, amount: U256) -> Result<(), Revert> {
+        let balance = self.balance_of(account).add(amount)?;
+        self.balances.insert(account, balance);
+        Ok(())
+    }
+
+    pub fn approve(&mut self, owner: Key, spender: Key, amount: U256) -> bool {
+        self.allowances.insert((owner, spender), amount);
+        true
+    }
+
+    pub fn transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<bool, Revert> {
+        self.transfer_(sender, recipient, amount)?;
+        Ok(true)
+    }
+
+    /// `transferFrom` called by `spender`.
+    pub fn transfer_from(
+        &mut self,
+        spender: Key,
+        owner: Key,
+        recipient: Key,
+        amount: U256,
+    ) -> Result<bool, Revert> {
+        self.transfer_(owner, recipient, amount)?;
+        let allowance = self.allowance(owner, spender);
+        if allowance < amount {
+            return Err("ERC20: transfer amount exceeds allowance");
+        }
+        self.approve(owner, spender, allowance - amount);
+        Ok(true)
+    }
+
+    fn transfer_(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), Revert> {
+        let sender_balance = self.balance_of(sender);
+        if sender_balance < amount {
+            return Err("ERC20: transfer amount exceeds balance");
+        }
+        self.balances.insert(sender, sender_balance - amount);
+        let recipient_balance = self.balance_of(recipient).add(amount)?;
+        self.balances.insert(recipient, recipient_balance);
+        Ok(())
+    }
+}
+
+/// `Staking.sol` deployed at `this`, together with the token it stakes.
+#[derive(Clone, Debug)]
+pub struct Staking {
+    this: Key,
+    accounting: Accounting,
+    pub token: Erc20,
+
+    stakes: BTreeMap<Key, U256>,
+
+    pub token_name: String,
+    pub staking_starts: U256,
+    pub staking_ends: U256,
+    pub staking_total: U256,
+    pub stake_balance: U256,
+    pub staked_total: U256,
+    pub withdraw_starts: U256,
+    pub withdraw_ends: U256,
+    pub total_reward: U256,
+    pub early_withdraw_reward: U256,
+    pub reward_balance: U256,
+    pub staked_balance: U256,
+}
+
+impl Staking {
+    #[allow(clippy::too_many_arguments)]
+    pub fn constructor(
+        this: Key,
+        token: Erc20,
+        token_name: &str,
+        staking_starts: u64,
+        staking_ends: u64,
+        withdraw_starts: u64,
+        withdraw_ends: u64,
+        staking_total: U256,
+        now: u64,
+        accounting: Accounting,
+    ) -> Result<Staking, Revert> {
+        let (staking_starts, staking_ends) = (U256::from(staking_starts), U256::from(staking_ends));
+        let (withdraw_starts, withdraw_ends) =
+            (U256::from(withdraw_starts), U256::from(withdraw_ends));
+        let now = U256::from(now);
+
+        require(
+            staking_starts > U256::zero(),
+            "Festaking: zero staking start time",
+        )?;
+        let staking_starts = if staking_starts < now {
+            now
+        } else {
+            staking_starts
+        };
+        require(
+            staking_ends > staking_starts,
+            "Festaking: staking end must be after staking starts",
+        )?;
+        require(
+            staking_total > U256::zero(),
+            "Festaking: stakingTotal must be positive",
+        )?;
+        require(
+            withdraw_starts >= staking_ends,
+            "Festaking: withdrawStarts must be after staking ends",
+        )?;
+        require(
+            withdraw_ends > withdraw_starts,
+            "Festaking: withdrawEnds must be after withdraw starts",
+        )?;
+
+        Ok(Staking {
+            this,
+            accounting,
+            token,
+            stakes: BTreeMap::new(),
+            token_name: token_name.to_string(),
+            staking_starts,
+            staking_ends,
+            staking_total,
+            stake_balance: U256::zero(),
+            staked_total: U256::zero(),
+            withdraw_starts,
+            withdraw_ends,
+            total_reward: U256::zero(),
+            early_withdraw_reward: U256::zero(),
+            reward_balance: U256::zero(),
+            staked_balance: U256::zero(),
+        })
+    }
+
+    pub fn address(&self) -> Key {
+        self.this
+    }
+
+    pub fn accounting(&self) -> Accounting {
+        self.accounting
+    }
+
+    pub fn stake_of(&self, staker: Key) -> U256 {
+        self.stakes.get(&staker).copied().unwrap_or_default()
+    }
+
+    /// Runs `call` as a transaction, discarding its effects if it reverts.
+    fn transaction<T>(
+        &mut self,
+        call: impl FnOnce(&mut Staking) -> Result<T, Revert>,
+    ) -> Result<T, Revert> {
+        let mut state = self.clone();
+        let result = call(&mut state)?;
+        *self = state;
+        Ok(result)
+    }
+
+    pub fn stake(&mut self, sender: Key, amount: U256, now: u64) -> Result<bool, Revert> {
+        self.transaction(|staking| {
+            positive(amount)?;
+            let staker = sender;
+            staking.stake_token(staker, amount, U256::from(now))
+        })
+    }
+
+    /// `_stakeToken`. It is `public` in `Staking.sol`, so anyone can spend a staker's allowance
+    /// to stake on its behalf.
+    fn stake_token(&mut self, staker_addr: Key, amount: U256, now: U256) -> Result<bool, Revert> {
+        after(now, self.staking_starts)?;
+        before(now, self.staking_ends)?;
+        positive(amount)?;
+        self.has_enough_token(staker_addr, amount)?;
+
+        let mut remaining_token = amount;
+        // Unchecked in Solidity 0.6, so it wraps.
+        if remaining_token > self.staking_total.overflowing_sub(remaining_token).0 {
+            remaining_token = self.staking_total.overflowing_sub(remaining_token).0;
+        }
+        require(
+            remaining_token > U256::zero(),
+            "Festaking: Staking cap is filled",
+        )?;
+        require(
+            remaining_token.overflowing_add(self.staked_total).0 <= self.staking_total,
+            "Festaking: this will increase staking amount pass the cap",
+        )?;
+
+        if !self.pay_me(staker_addr, remaining_token)? {
+            return Ok(false);
+        }
+
+        if remaining_token < amount {
+            let refund = amount.sub(remaining_token)?;
+            self.pay_to(staker_addr, staker_addr, refund)?;
+        }
+
+        self.staked_total = self.staked_total.add(remaining_token)?;
+        if self.accounting == Accounting::Booked {
+            self.staked_balance = self.staked_balance.add(remaining_token)?;
+        }
+        let stake = self.stake_of(staker_addr).add(remaining_token)?;
+        self.stakes.insert(staker_addr, stake);
+        Ok(true)
+    }
+
+    fn has_enough_token(&self, staker: Key, amount: U256) -> Result<(), Revert> {
+        let our_allowance = self.token.allowance(staker, self.this);
+        require(
+            amount <= our_allowance,
+            "Festaking: Make sure to add enough allowance",
+        )
+    }
+
+    fn has_allowance(&self, allower: Key, amount: U256) -> Result<(), Revert> {
+        let our_allowance = self.token.allowance(allower, self.this);
+        require(
+            amount <= our_allowance,
+            "Festaking: Make sure to add enough allowance",
+        )
+    }
+
+    fn pay_me(&mut self, payer: Key, amount: U256) -> Result<bool, Revert> {
+        let this = self.this;
+        self.pay_to(payer, this, amount)
+    }
+
+    fn pay_to(&mut self, allower: Key, receiver: Key, amount: U256) -> Result<bool, Revert> {
+        self.has_allowance(allower, amount)?;
+        let this = self.this;
+        self.token.transfer_from(this, allower, receiver, amount)
+    }
+
+    fn pay_direct(&mut self, to: Key, amount: U256) -> Result<bool, Revert> {
+        positive(amount)?;
+        let this = self.this;
+        self.token.transfer(this, to, amount)
+    }
+
+    pub fn add_reward(
+        &mut self,
+        sender: Key,
+        reward_amount: U256,
+        withdrawable_amount: U256,
+        now: u64,
+    ) -> Result<bool, Revert> {
+        self.transaction(|staking| {
+            before(U256::from(now), staking.withdraw_starts)?;
+            staking.has_allowance(sender, reward_amount)?;
+            require(
+                reward_amount > U256::zero(),
+                "Festaking: reward must be positive",
+            )?;
+            require(
+                withdrawable_amount >= U256::zero(),
+                "Festaking: withdrawable amount cannot be negative",
+            )?;
+            require(
+                withdrawable_amount <= reward_amount,
+                "Festaking: withdrawable amount must be less than or equal to the reward amount",
+            )?;
+            let from = sender;
+            if !staking.pay_me(from, reward_amount)? {
+                return Ok(false);
+            }
+
+            staking.total_reward = staking.total_reward.add(reward_amount)?;
+            staking.reward_balance = staking.total_reward;
+            staking.early_withdraw_reward =
+                staking.early_withdraw_reward.add(withdrawable_amount)?;
+            Ok(true)
+        })
+    }
+
+    pub fn withdraw(&mut self, sender: Key, amount: U256, now: u64) -> Result<bool, Revert> {
+        self.transaction(|staking| {
+            let now = U256::from(now);
+            after(now, staking.withdraw_starts)?;
+            positive(amount)?;
+            let from = sender;
+            require(
+                amount <= staking.stake_of(from),
+                "Festaking: not enough balance",
+            )?;
+            if now < staking.withdraw_ends {
+                staking.withdraw_early(from, amount, now)
+            } else {
+                staking.withdraw_after_close(from, amount)
+            }
+        })
+    }
+
+    fn withdraw_early(&mut self, from: Key, amount: U256, now: U256) -> Result<bool, Revert> {
+        let denom = self
+            .withdraw_ends
+            .sub(self.staking_ends)?
+            .mul(self.staked_total)?;
+        let reward = now
+            .sub(self.staking_ends)?
+            .mul(self.early_withdraw_reward)?
+            .mul(amount)?
+            .div(denom)?;
+        let pay_out = amount.add(reward)?;
+        self.reward_balance = self.reward_balance.sub(reward)?;
+        self.staked_balance = self.staked_balance.sub(amount)?;
+        let stake = self.stake_of(from).sub(amount)?;
+        self.stakes.insert(from, stake);
+        self.pay_direct(from, pay_out)
+    }
+
+    fn withdraw_after_close(&mut self, from: Key, amount: U256) -> Result<bool, Revert> {
+        let reward = self.reward_balance.mul(amount)?.div(self.staked_balance)?;
+        let pay_out = amount.add(reward)?;
+        if self.accounting == Accounting::Booked {
+            self.reward_balance = self.reward_balance.sub(reward)?;
+            self.staked_balance = self.staked_balance.sub(amount)?;
+        }
+        let stake = self.stake_of(from).sub(amount)?;
+        self.stakes.insert(from, stake);
+        self.pay_direct(from, pay_out)
+    }
+}
+
+fn positive(amount: U256) -> Result<(), Revert> {
+    require(amount > U256::from(2), "Festaking: negative amount")
+}
+
+fn after(now: U256, event_time: U256) -> Result<(), Revert> {
+    require(now >= event_time, "Festaking: bad timing for the request")
+}
+
+fn before(now: U256, event_time: U256) -> Result<(), Revert> {
+    require(now < event_time, "Festaking: bad timing for the request")
+}
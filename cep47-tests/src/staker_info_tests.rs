@@ -0,0 +1,129 @@
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, U256};
+use cep47::StakerInfo;
+
+use crate::staking_instance::{account, StakingEnv, WITHDRAW_ENDS, WITHDRAW_STARTS};
+
+const STAKED: u64 = 1_000;
+const REWARD: u64 = 1_000;
+const WITHDRAWABLE: u64 = 500;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    env.mint(token, Key::Account(staker), U256::from(STAKED * 2));
+    env.approve(token, staker, pool, U256::from(STAKED * 2));
+    env.mint(
+        token,
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        U256::from(REWARD),
+    );
+    env.approve(token, *DEFAULT_ACCOUNT_ADDR, pool, U256::from(REWARD));
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+fn add_reward(env: &mut StakingEnv, pool: ContractHash) {
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "add_reward",
+        runtime_args! {
+            "reward_amount" => U256::from(REWARD),
+            "withdrawable_amount" => U256::from(WITHDRAWABLE)
+        },
+        0,
+    );
+}
+
+fn staker_info(
+    env: &mut StakingEnv,
+    pool: ContractHash,
+    staker: AccountHash,
+    time: u64,
+) -> StakerInfo {
+    let ((staked, pending_reward, projected_reward), (first_staked_at, deposits)) = env.view(
+        pool,
+        "staker_info",
+        runtime_args! { "staker" => Key::Account(staker) },
+        time,
+    );
+    StakerInfo {
+        staked,
+        pending_reward,
+        projected_reward,
+        first_staked_at,
+        deposits,
+    }
+}
+
+#[test]
+fn test_staker_info_counts_deposits() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+    env.call(staker, pool, "stake", stake_args(STAKED), 20);
+
+    let info = staker_info(&mut env, pool, staker, 30);
+    assert_eq!(info.staked, U256::from(STAKED * 2));
+    assert_eq!(info.first_staked_at, 10);
+    assert_eq!(info.deposits, 2);
+    assert_eq!(info.pending_reward, U256::zero());
+}
+
+#[test]
+fn test_staker_info_is_readable_from_dictionary() {
+    let (mut env, _, pool, staker) = deploy();
+    let stored: Option<StakerInfo> =
+        env.pool_dictionary_item(pool, "staker_info", Key::Account(staker));
+    assert_eq!(stored, None);
+
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+    let stored: StakerInfo = env
+        .pool_dictionary_item(pool, "staker_info", Key::Account(staker))
+        .unwrap();
+    assert_eq!(stored, staker_info(&mut env, pool, staker, 10));
+}
+
+#[test]
+fn test_staker_info_reports_pending_and_projected_rewards() {
+    let (mut env, _, pool, staker) = deploy();
+    add_reward(&mut env, pool);
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+
+    // Halfway from staking_ends to withdraw_ends, half of the early reward has accrued.
+    let info = staker_info(&mut env, pool, staker, WITHDRAW_STARTS);
+    assert_eq!(info.pending_reward, U256::from(WITHDRAWABLE / 2));
+    assert_eq!(info.projected_reward, U256::from(REWARD));
+
+    let info = staker_info(&mut env, pool, staker, WITHDRAW_ENDS);
+    assert_eq!(info.pending_reward, U256::from(REWARD));
+}
+
+#[test]
+fn test_withdraw_pays_reward_on_top_of_principal() {
+    let (mut env, token, pool, staker) = deploy();
+    add_reward(&mut env, pool);
+    env.call(staker, pool, "stake", stake_args(STAKED), 10);
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(
+        env.balance_of(token, Key::Account(staker)),
+        U256::from(STAKED * 2 + REWARD)
+    );
+    assert_eq!(env.query::<U256>(pool, "reward_balance"), U256::zero());
+}
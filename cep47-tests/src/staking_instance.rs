@@ -0,0 +1,361 @@
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state, execution};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    runtime_args, ApiError, CLTyped, CLValue, ContractHash, Key, NamedArg, PublicKey, RuntimeArgs,
+    SecretKey, U256, U512,
+};
+
+pub const POOL_NAME: &str = "pool";
+pub const TOKEN_NAME: &str = "token";
+pub const STAKING_ENDS: u64 = 1_000;
+pub const WITHDRAW_STARTS: u64 = 2_000;
+pub const WITHDRAW_ENDS: u64 = 3_000;
+pub const INITIAL_BALANCE: u64 = 10_000_000_000_000;
+
+pub struct StakingEnv {
+    pub builder: InMemoryWasmTestBuilder,
+}
+
+pub fn account(seed: u8) -> (PublicKey, AccountHash) {
+    let public_key = PublicKey::from(&SecretKey::ed25519_from_bytes([seed; 32]).unwrap());
+    let account_hash = public_key.to_account_hash();
+    (public_key, account_hash)
+}
+
+/// Install arguments of a pool staking `token`, or native CSPR when `token` is `None`.
+pub fn pool_args(token: Option<ContractHash>, staking_total: U256) -> RuntimeArgs {
+    let address = token
+        .map(|token| Key::from(token).to_formatted_string())
+        .unwrap_or_default();
+    runtime_args! {
+        "contract_name" => POOL_NAME,
+        "name" => "pool",
+        "address" => address,
+        "staking_starts" => 0u64,
+        "staking_ends" => STAKING_ENDS,
+        "withdraw_starts" => WITHDRAW_STARTS,
+        "withdraw_ends" => WITHDRAW_ENDS,
+        "staking_total" => staking_total,
+        "min_stake" => U256::from(2),
+        "max_stake_per_address" => U256::zero(),
+        "max_stakers" => 0u64,
+        "asset_kind" => if token.is_some() { 0u8 } else { 3u8 },
+        "caller_policy" => 0u8
+    }
+}
+
+/// Bytes the mock token and the pool key their dictionaries by, in hex.
+fn key_bytes(owner: Key) -> [u8; 32] {
+    match owner {
+        Key::Account(account_hash) => account_hash.value(),
+        Key::Hash(hash) => hash,
+        _ => panic!("unsupported owner"),
+    }
+}
+
+/// The 32-byte blake2b hash contracts compute with `runtime::blake2b`.
+pub fn blake2b(bytes: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(bytes);
+    hasher.finalize_variable(|result| hash.copy_from_slice(result));
+    hash
+}
+
+/// Leaf of the pool's allowlist, as `merkle::leaf` computes it.
+pub fn merkle_leaf(staker: AccountHash, allocation: u64) -> [u8; 32] {
+    let mut bytes = Key::Account(staker).to_bytes().unwrap();
+    bytes.extend(U256::from(allocation).to_bytes().unwrap());
+    blake2b(&bytes)
+}
+
+fn view_args(pool: ContractHash, entry_point: &str, args: RuntimeArgs) -> RuntimeArgs {
+    runtime_args! {
+        "pool" => pool,
+        "entry_point" => entry_point,
+        "args" => args.to_bytes().unwrap()
+    }
+}
+
+/// Returns `args` with `name` set to `value`. `RuntimeArgs::insert` appends and the first argument
+/// of a name wins, so it can't override one.
+pub fn with_arg<T: CLTyped + ToBytes>(args: RuntimeArgs, name: &str, value: T) -> RuntimeArgs {
+    let mut named_args: Vec<NamedArg> = args
+        .named_args()
+        .filter(|arg| arg.name() != name)
+        .cloned()
+        .collect();
+    named_args.push(NamedArg::new(
+        name.to_string(),
+        CLValue::from_t(value).unwrap(),
+    ));
+    RuntimeArgs::from(named_args)
+}
+
+impl StakingEnv {
+    pub fn new() -> StakingEnv {
+        let mut builder = InMemoryWasmTestBuilder::default();
+        builder.run_genesis(&*DEFAULT_RUN_GENESIS_REQUEST).commit();
+        StakingEnv { builder }
+    }
+
+    pub fn fund(&mut self, target: AccountHash) {
+        let request = ExecuteRequestBuilder::transfer(
+            *DEFAULT_ACCOUNT_ADDR,
+            runtime_args! {
+                "target" => target,
+                "amount" => U512::from(INITIAL_BALANCE),
+                "id" => Option::<u64>::None
+            },
+        )
+        .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    /// Installs `wasm` and returns the contract hash it stored under `{contract_name}_contract_hash`.
+    pub fn install(
+        &mut self,
+        sender: AccountHash,
+        wasm: &str,
+        contract_name: &str,
+        args: RuntimeArgs,
+    ) -> ContractHash {
+        let request = ExecuteRequestBuilder::standard(sender, wasm, args).build();
+        self.builder.exec(request).expect_success().commit();
+        self.builder
+            .get_expected_account(sender)
+            .named_keys()
+            .get(&format!("{}_contract_hash", contract_name))
+            .and_then(|key| key.into_hash())
+            .map(ContractHash::new)
+            .unwrap()
+    }
+
+    pub fn install_token(&mut self) -> ContractHash {
+        self.install(
+            *DEFAULT_ACCOUNT_ADDR,
+            "mock-token.wasm",
+            TOKEN_NAME,
+            runtime_args! { "contract_name" => TOKEN_NAME },
+        )
+    }
+
+    pub fn install_pool(&mut self, token: Option<ContractHash>, staking_total: U256) -> ContractHash {
+        self.install_pool_with(pool_args(token, staking_total))
+    }
+
+    pub fn install_pool_with(&mut self, args: RuntimeArgs) -> ContractHash {
+        self.install(*DEFAULT_ACCOUNT_ADDR, "cep47-token.wasm", POOL_NAME, args)
+    }
+
+    pub fn call(
+        &mut self,
+        sender: AccountHash,
+        contract: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) {
+        let request =
+            ExecuteRequestBuilder::contract_call_by_hash(sender, contract, entry_point, args)
+                .with_block_time(time)
+                .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    /// Runs session `wasm` as `sender`.
+    pub fn run_session(&mut self, sender: AccountHash, wasm: &str, args: RuntimeArgs, time: u64) {
+        let request = ExecuteRequestBuilder::standard(sender, wasm, args)
+            .with_block_time(time)
+            .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    /// Runs session `wasm` that must revert and returns the [`ApiError`] it reverted with.
+    pub fn run_session_expect_revert(
+        &mut self,
+        sender: AccountHash,
+        wasm: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) -> ApiError {
+        let request = ExecuteRequestBuilder::standard(sender, wasm, args)
+            .with_block_time(time)
+            .build();
+        self.builder.exec(request).expect_failure().commit();
+        self.revert_error()
+    }
+
+    /// Runs a call that must revert and returns the [`ApiError`] it reverted with.
+    pub fn call_expect_revert(
+        &mut self,
+        sender: AccountHash,
+        contract: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) -> ApiError {
+        let request =
+            ExecuteRequestBuilder::contract_call_by_hash(sender, contract, entry_point, args)
+                .with_block_time(time)
+                .build();
+        self.builder.exec(request).expect_failure().commit();
+        self.revert_error()
+    }
+
+    fn revert_error(&self) -> ApiError {
+        match self.builder.get_error() {
+            Some(engine_state::Error::Exec(execution::Error::Revert(api_error))) => api_error,
+            error => panic!("expected a revert, got {:?}", error),
+        }
+    }
+
+    pub fn mint(&mut self, token: ContractHash, owner: Key, amount: U256) {
+        self.call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "mint",
+            runtime_args! { "owner" => owner, "amount" => amount },
+            0,
+        );
+    }
+
+    /// Approves `pool` to pull `amount` of `token` from `owner`, as staking and funding require.
+    pub fn approve(
+        &mut self,
+        token: ContractHash,
+        owner: AccountHash,
+        pool: ContractHash,
+        amount: U256,
+    ) {
+        let spender = self.pool_key(pool);
+        self.call(
+            owner,
+            token,
+            "approve",
+            runtime_args! { "spender" => spender, "amount" => amount },
+            0,
+        );
+    }
+
+    /// Calls the view `entry_point` of `pool` through the pool-view session and returns its result.
+    pub fn view<T: FromBytes + CLTyped>(
+        &mut self,
+        pool: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) -> T {
+        self.run_session(
+            *DEFAULT_ACCOUNT_ADDR,
+            "pool-view.wasm",
+            view_args(pool, entry_point, args),
+            time,
+        );
+        self.builder
+            .query(
+                None,
+                Key::Account(*DEFAULT_ACCOUNT_ADDR),
+                &[entry_point.to_string()],
+            )
+            .unwrap()
+            .as_cl_value()
+            .unwrap()
+            .clone()
+            .into_t()
+            .unwrap()
+    }
+
+    /// Calls a view entry point that must revert and returns the [`ApiError`] it reverted with.
+    pub fn view_expect_revert(
+        &mut self,
+        pool: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        time: u64,
+    ) -> ApiError {
+        self.run_session_expect_revert(
+            *DEFAULT_ACCOUNT_ADDR,
+            "pool-view.wasm",
+            view_args(pool, entry_point, args),
+            time,
+        )
+    }
+
+    /// Reads the item of `owner` in the pool dictionary `dictionary`, `None` when never written.
+    pub fn pool_dictionary_item<T: FromBytes + CLTyped>(
+        &self,
+        pool: ContractHash,
+        dictionary: &str,
+        owner: Key,
+    ) -> Option<T> {
+        let dictionary = self
+            .builder
+            .get_contract(pool)
+            .unwrap()
+            .named_keys()
+            .get(dictionary)
+            .and_then(|key| key.into_uref())
+            .unwrap();
+        self.builder
+            .query_dictionary_item(None, dictionary, &hex::encode(key_bytes(owner)))
+            .ok()
+            .and_then(|value| value.as_cl_value().cloned())
+            .map(|value| value.into_t().unwrap())
+    }
+
+    /// Reads a value the contract stores under the named key `name`.
+    pub fn query<T: FromBytes + CLTyped>(&self, contract: ContractHash, name: &str) -> T {
+        self.builder
+            .query(None, Key::from(contract), &[name.to_string()])
+            .unwrap()
+            .as_cl_value()
+            .unwrap()
+            .clone()
+            .into_t()
+            .unwrap()
+    }
+
+    pub fn balance_of(&self, token: ContractHash, owner: Key) -> U256 {
+        self.token_dictionary_item(token, "balances", &hex::encode(key_bytes(owner)))
+    }
+
+    /// Allowance of `spender` over the tokens of `owner`, zero when never approved.
+    pub fn allowance(&self, token: ContractHash, owner: Key, spender: Key) -> U256 {
+        let mut bytes = key_bytes(owner).to_vec();
+        bytes.extend_from_slice(&key_bytes(spender));
+        self.token_dictionary_item(token, "allowances", &hex::encode(blake2b(&bytes)))
+    }
+
+    fn token_dictionary_item(&self, token: ContractHash, dictionary: &str, item_key: &str) -> U256 {
+        let dictionary = self
+            .builder
+            .get_contract(token)
+            .unwrap()
+            .named_keys()
+            .get(dictionary)
+            .and_then(|key| key.into_uref())
+            .unwrap();
+        self.builder
+            .query_dictionary_item(None, dictionary, item_key)
+            .ok()
+            .and_then(|value| value.as_cl_value().cloned())
+            .map(|value| value.into_t().unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Key the pool is known by to token contracts, its contract package hash.
+    pub fn pool_key(&self, pool: ContractHash) -> Key {
+        let package = self.builder.get_contract(pool).unwrap().contract_package_hash();
+        Key::Hash(package.value())
+    }
+}
@@ -0,0 +1,131 @@
+use casper_engine_test_support::{ExecuteRequestBuilder, DEFAULT_ACCOUNT_ADDR};
+use casper_types::{
+    account::AccountHash, runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::staking_instance::{account, pool_args, with_arg, StakingEnv, WITHDRAW_ENDS};
+
+const STAKED: u64 = 1_000;
+const INSUFFICIENT_STAKE: u16 = 23;
+const INVALID_ADDRESS: u16 = 27;
+const PAUSED: u16 = 29;
+
+fn deploy() -> (StakingEnv, ContractHash, ContractHash, AccountHash) {
+    let mut env = StakingEnv::new();
+    let (_, staker) = account(4);
+    env.fund(staker);
+    let token = env.install_token();
+    let pool = env.install_pool(Some(token), U256::from(STAKED * 10));
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        token,
+        "mint",
+        runtime_args! { "owner" => Key::Account(staker), "amount" => U256::from(STAKED * 10) },
+        0,
+    );
+    env.approve(token, staker, pool, U256::from(STAKED * 10));
+    (env, token, pool, staker)
+}
+
+fn stake_args(amount: u64) -> RuntimeArgs {
+    runtime_args! {
+        "amount" => U256::from(amount),
+        "proof" => Vec::<[u8; 32]>::new(),
+        "allocation" => U256::zero()
+    }
+}
+
+#[test]
+fn test_first_stake_starts_from_zero() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    env.call(staker, pool, "stake", stake_args(STAKED), 1);
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::from(STAKED * 2));
+    assert_eq!(env.query::<U256>(pool, "staked_total"), U256::from(STAKED * 2));
+}
+
+#[test]
+fn test_withdraw_above_stake_reverts_with_insufficient_stake() {
+    let (mut env, token, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    let error = env.call_expect_revert(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED + 1) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(error, ApiError::User(INSUFFICIENT_STAKE));
+    assert_eq!(env.balance_of(token, env.pool_key(pool)), U256::from(STAKED));
+}
+
+#[test]
+fn test_withdraw_without_stake_reverts_with_insufficient_stake() {
+    let (mut env, _, pool, _) = deploy();
+    let (_, stranger) = account(5);
+    env.fund(stranger);
+    let error = env.call_expect_revert(
+        stranger,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(error, ApiError::User(INSUFFICIENT_STAKE));
+}
+
+#[test]
+fn test_install_stores_token_contract_hash() {
+    let (mut env, token, pool, _) = deploy();
+    assert_eq!(
+        env.query::<Option<ContractHash>>(pool, "address"),
+        Some(token)
+    );
+    let address: Option<ContractHash> = env.view(pool, "address", RuntimeArgs::new(), 0);
+    assert_eq!(address, Some(token));
+}
+
+#[test]
+fn test_install_rejects_malformed_address() {
+    let account_address = Key::Account(AccountHash::new([7u8; 32])).to_formatted_string();
+    for address in ["hash-not-hex", "", account_address.as_str()] {
+        let mut env = StakingEnv::new();
+        let args = pool_args(None, U256::from(STAKED));
+        let args = with_arg(args, "address", address.to_string());
+        let args = with_arg(args, "asset_kind", 0u8);
+        let request =
+            ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, "cep47-token.wasm", args)
+                .build();
+        env.builder.exec(request).expect_failure().commit();
+        let error = format!("{:?}", env.builder.get_error());
+        assert!(
+            error.contains(&format!("User({})", INVALID_ADDRESS)),
+            "{}: {}",
+            address,
+            error
+        );
+    }
+}
+
+#[test]
+fn test_paused_pool_rejects_stake_but_not_withdraw() {
+    let (mut env, _, pool, staker) = deploy();
+    env.call(staker, pool, "stake", stake_args(STAKED), 0);
+    env.call(
+        *DEFAULT_ACCOUNT_ADDR,
+        pool,
+        "set_paused",
+        runtime_args! { "paused" => true },
+        0,
+    );
+    let error = env.call_expect_revert(staker, pool, "stake", stake_args(STAKED), 1);
+    assert_eq!(error, ApiError::User(PAUSED));
+    env.call(
+        staker,
+        pool,
+        "withdraw",
+        runtime_args! { "amount" => U256::from(STAKED) },
+        WITHDRAW_ENDS,
+    );
+    assert_eq!(env.query::<U256>(pool, "staked_balance"), U256::zero());
+}
@@ -3,17 +3,17 @@
 
 extern crate alloc;
 
-use alloc::{boxed::Box, collections::BTreeSet, format, string::String};
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
 use alloc::vec;
 use casper_contract::{
     contract_api::{runtime, storage},
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_types::{
-    runtime_args, CLType, CLTyped, Key, Group, Parameter, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess,
+    runtime_args, CLTyped, ContractHash, Key, Group, Parameter, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess,
     EntryPointType, EntryPoints, RuntimeArgs, URef, U256,
 };
-use cep47::{CEP20STK};
+use cep47::{AssetKind, CallerPolicy, CEP20STK, Phase, StakerInfo};
 use contract_utils::{ContractContext, OnChainContractStorage};
 
 #[derive(Default)]
@@ -27,8 +27,18 @@ impl ContractContext<OnChainContractStorage> for Token {
 
 impl CEP20STK<OnChainContractStorage> for Token {}
 impl Token {
-    fn constructor(&mut self, name: String, address: String, staking_starts: u64, staking_ends: u64, withdraw_starts: u64, withdraw_ends: u64, staking_total: U256) {
-        CEP20STK::init(self, name, address, staking_starts, staking_ends, withdraw_starts, withdraw_ends, staking_total);
+    #[allow(clippy::too_many_arguments)]
+    fn constructor(&mut self, name: String, address: String, staking_starts: u64, staking_ends: u64, withdraw_starts: u64, withdraw_ends: u64, staking_total: U256, min_stake: U256, max_stake_per_address: U256, max_stakers: u64, asset_kind: AssetKind, caller_policy: CallerPolicy) {
+        CEP20STK::init(self, name, address, staking_starts, staking_ends, withdraw_starts, withdraw_ends, staking_total, min_stake, max_stake_per_address, max_stakers, asset_kind, caller_policy);
+    }
+}
+
+/// Reads the `purse` funding a deposit, which is only passed to native pools.
+fn purse_arg() -> Option<URef> {
+    if Token::default().native() {
+        Some(runtime::get_named_arg::<URef>("purse"))
+    } else {
+        None
     }
 }
 
@@ -41,8 +51,13 @@ fn constructor() {
     let withdraw_starts: u64 = runtime::get_named_arg::<u64>("withdraw_starts");
     let withdraw_ends: u64 = runtime::get_named_arg::<u64>("withdraw_ends");
     let staking_total: U256 = runtime::get_named_arg::<U256>("staking_total");
+    let min_stake: U256 = runtime::get_named_arg::<U256>("min_stake");
+    let max_stake_per_address: U256 = runtime::get_named_arg::<U256>("max_stake_per_address");
+    let max_stakers: u64 = runtime::get_named_arg::<u64>("max_stakers");
+    let asset_kind: AssetKind = runtime::get_named_arg::<AssetKind>("asset_kind");
+    let caller_policy: CallerPolicy = runtime::get_named_arg::<CallerPolicy>("caller_policy");
 
-    Token::default().constructor(name, address, staking_starts, staking_ends, withdraw_starts, withdraw_ends, staking_total);
+    Token::default().constructor(name, address, staking_starts, staking_ends, withdraw_starts, withdraw_ends, staking_total, min_stake, max_stake_per_address, max_stakers, asset_kind, caller_policy);
 }
 
 #[no_mangle]
@@ -57,6 +72,38 @@ fn address() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+#[no_mangle]
+fn native() {
+    let ret = Token::default().native();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn asset_kind() {
+    let ret = Token::default().asset_kind();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn caller_policy() {
+    let ret = Token::default().caller_policy();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn is_allowed_contract() {
+    let contract_package_hash = runtime::get_named_arg::<ContractPackageHash>("contract_package_hash");
+    let ret = Token::default().is_allowed_contract(contract_package_hash);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_allowed_contract() {
+    let contract_package_hash = runtime::get_named_arg::<ContractPackageHash>("contract_package_hash");
+    let allowed = runtime::get_named_arg::<bool>("allowed");
+    Token::default().set_allowed_contract(contract_package_hash, allowed).unwrap_or_revert();
+}
+
 #[no_mangle]
 fn staking_starts() {
     let ret = Token::default().staking_starts();
@@ -65,7 +112,7 @@ fn staking_starts() {
 
 #[no_mangle]
 fn staking_ends() {
-    let ret = Token::default().staking_starts();
+    let ret = Token::default().staking_ends();
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
@@ -87,6 +134,123 @@ fn staking_total() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+#[no_mangle]
+fn min_stake() {
+    let ret = Token::default().min_stake();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn max_stake_per_address() {
+    let ret = Token::default().max_stake_per_address();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn max_stakers() {
+    let ret = Token::default().max_stakers();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_stake_limits() {
+    let min_stake = runtime::get_named_arg::<U256>("min_stake");
+    let max_stake_per_address = runtime::get_named_arg::<U256>("max_stake_per_address");
+    let max_stakers = runtime::get_named_arg::<u64>("max_stakers");
+    Token::default().set_stake_limits(min_stake, max_stake_per_address, max_stakers).unwrap_or_revert();
+}
+
+#[no_mangle]
+fn penalty_bps() {
+    let ret = Token::default().penalty_bps();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn penalty_decays() {
+    let ret = Token::default().penalty_decays();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn penalty_treasury() {
+    let ret = Token::default().penalty_treasury();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_penalty() {
+    let penalty_bps = runtime::get_named_arg::<u64>("penalty_bps");
+    let decays = runtime::get_named_arg::<bool>("decays");
+    let treasury = runtime::get_named_arg::<Option<Key>>("treasury");
+    Token::default().set_penalty(penalty_bps, decays, treasury).unwrap_or_revert();
+}
+
+#[no_mangle]
+fn owner() {
+    let ret = Token::default().owner();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn merkle_root() {
+    let ret = Token::default().merkle_root();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn paused() {
+    let ret = Token::default().paused();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn set_paused() {
+    let paused = runtime::get_named_arg::<bool>("paused");
+    Token::default().set_paused(paused).unwrap_or_revert();
+}
+
+#[no_mangle]
+fn set_merkle_root() {
+    let merkle_root = runtime::get_named_arg::<Option<[u8; 32]>>("merkle_root");
+    Token::default().set_merkle_root(merkle_root).unwrap_or_revert();
+}
+
+#[no_mangle]
+fn staker_count() {
+    let ret = Token::default().staker_count();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn stakers() {
+    let offset = runtime::get_named_arg::<u64>("offset");
+    let limit = runtime::get_named_arg::<u64>("limit");
+    let ret = Token::default().stakers(offset, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn staked_at() {
+    let staker = runtime::get_named_arg::<Key>("staker");
+    let timestamp = runtime::get_named_arg::<u64>("timestamp");
+    let ret = Token::default().staked_at(staker, timestamp);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn total_staked_at() {
+    let timestamp = runtime::get_named_arg::<u64>("timestamp");
+    let ret = Token::default().total_staked_at(timestamp);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn current_phase() {
+    let ret = Token::default().current_phase();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 #[no_mangle]
 fn amount_staked() {
     let staker = runtime::get_named_arg::<Key>("staker");
@@ -94,10 +258,35 @@ fn amount_staked() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+#[no_mangle]
+fn staker_info() {
+    let staker = runtime::get_named_arg::<Key>("staker");
+    let ret = Token::default().staker_info(staker).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn preview_stake() {
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let ret = Token::default().preview_stake(amount).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn preview_withdraw() {
+    let staker = runtime::get_named_arg::<Key>("staker");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let ret = Token::default().preview_withdraw(staker, amount).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 #[no_mangle]
 fn stake() {
     let amount = runtime::get_named_arg::<U256>("amount");
-    let ret = Token::default().stake(amount).unwrap_or_revert();
+    let proof = runtime::get_named_arg::<Vec<[u8; 32]>>("proof");
+    let allocation = runtime::get_named_arg::<U256>("allocation");
+    let purse = purse_arg();
+    let ret = Token::default().stake(amount, proof, allocation, purse).unwrap_or_revert();
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
@@ -108,11 +297,46 @@ fn withdraw() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+#[no_mangle]
+fn claim_reward() {
+    let ret = Token::default().claim_reward().unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn compound() {
+    let proof = runtime::get_named_arg::<Vec<[u8; 32]>>("proof");
+    let allocation = runtime::get_named_arg::<U256>("allocation");
+    let ret = Token::default().compound(proof, allocation).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn finalize() {
+    let ret = Token::default().finalize().unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn claim_refund() {
+    let ret = Token::default().claim_refund().unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn recover_token() {
+    let token_hash = runtime::get_named_arg::<ContractHash>("token_hash");
+    let recipient = runtime::get_named_arg::<Key>("recipient");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    Token::default().recover_token(token_hash, recipient, amount).unwrap_or_revert();
+}
+
 #[no_mangle]
 fn add_reward() {
     let reward_amount = runtime::get_named_arg::<U256>("reward_amount");
     let withdrawable_amount = runtime::get_named_arg::<U256>("withdrawable_amount");
-    let ret = Token::default().add_reward(reward_amount,withdrawable_amount).unwrap_or_revert();
+    let purse = purse_arg();
+    let ret = Token::default().add_reward(reward_amount,withdrawable_amount, purse).unwrap_or_revert();
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
@@ -127,7 +351,12 @@ fn call() {
     let withdraw_starts: u64 = runtime::get_named_arg::<u64>("withdraw_starts");
     let withdraw_ends: u64 = runtime::get_named_arg::<u64>("withdraw_ends");
     let staking_total: U256 = runtime::get_named_arg::<U256>("staking_total");
-    // let contract_name: String = runtime::get_named_arg("contract_name");
+    let min_stake: U256 = runtime::get_named_arg::<U256>("min_stake");
+    let max_stake_per_address: U256 = runtime::get_named_arg::<U256>("max_stake_per_address");
+    let max_stakers: u64 = runtime::get_named_arg::<u64>("max_stakers");
+    let asset_kind: AssetKind = runtime::get_named_arg::<AssetKind>("asset_kind");
+    let caller_policy: CallerPolicy = runtime::get_named_arg::<CallerPolicy>("caller_policy");
+    let contract_name: String = runtime::get_named_arg("contract_name");
 
 
     /* 
@@ -138,13 +367,19 @@ casper-client put-deploy \
   --secret-key ./keys/secret_key.pem \
   --session-path ./target/wasm32-unknown-unknown/release/cep47-token.wasm \
   --payment-amount 80000000000 \
+  --session-arg "contract_name:string='ferrumx_staking'" \
   --session-arg "name:string='FerrumX'" \
   --session-arg "address:string='hash-7e3f01576650a939a96c2caa6dcc19df8d2ef1882e4b6603a375234e22e07e4f'" \
-  --session-arg "staking_starts:u64='1653993649'" \
-  --session-arg "staking_ends:u64='1653994249'" \
-  --session-arg "withdraw_starts:u64='1653994549'" \
-  --session-arg "withdraw_ends:u64='1653994249'" \
-  --session-arg "staking_total:U256='500000'" 
+  --session-arg "staking_starts:u64='1654041600000'" \
+  --session-arg "staking_ends:u64='1654646400000'" \
+  --session-arg "withdraw_starts:u64='1655251200000'" \
+  --session-arg "withdraw_ends:u64='1656633600000'" \
+  --session-arg "staking_total:U256='500000'" \
+  --session-arg "min_stake:U256='2'" \
+  --session-arg "max_stake_per_address:U256='0'" \
+  --session-arg "max_stakers:u64='0'" \
+  --session-arg "asset_kind:u8='0'" \
+  --session-arg "caller_policy:u8='0'"
 
     */
     
@@ -156,7 +391,12 @@ casper-client put-deploy \
         "staking_ends" => staking_ends,
         "withdraw_starts" => withdraw_starts,
         "withdraw_ends" => withdraw_ends,
-        "staking_total" => staking_total
+        "staking_total" => staking_total,
+        "min_stake" => min_stake,
+        "max_stake_per_address" => max_stake_per_address,
+        "max_stakers" => max_stakers,
+        "asset_kind" => asset_kind,
+        "caller_policy" => caller_policy
     };
 
     let (contract_hash, _) = storage::new_contract(
@@ -207,7 +447,12 @@ fn get_entry_points() -> EntryPoints {
             Parameter::new("staking_ends", u64::cl_type()),
             Parameter::new("withdraw_starts", u64::cl_type()),
             Parameter::new("withdraw_ends", u64::cl_type()),
-            Parameter::new("staking_total", U256::cl_type())
+            Parameter::new("staking_total", U256::cl_type()),
+            Parameter::new("min_stake", U256::cl_type()),
+            Parameter::new("max_stake_per_address", U256::cl_type()),
+            Parameter::new("max_stakers", u64::cl_type()),
+            Parameter::new("asset_kind", AssetKind::cl_type()),
+            Parameter::new("caller_policy", CallerPolicy::cl_type())
         ],
         <()>::cl_type(),
         EntryPointAccess::Groups(vec![Group::new("constructor")]),
@@ -223,10 +468,50 @@ fn get_entry_points() -> EntryPoints {
     entry_points.add_entry_point(EntryPoint::new(
         "address",
         vec![],
-        String::cl_type(),
+        Option::<ContractHash>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     )); 
+    entry_points.add_entry_point(EntryPoint::new(
+        "native",
+        vec![],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "asset_kind",
+        vec![],
+        AssetKind::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "caller_policy",
+        vec![],
+        CallerPolicy::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_allowed_contract",
+        vec![
+            Parameter::new("contract_package_hash", ContractPackageHash::cl_type())
+        ],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_allowed_contract",
+        vec![
+            Parameter::new("contract_package_hash", ContractPackageHash::cl_type()),
+            Parameter::new("allowed", bool::cl_type())
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "staking_starts",
         vec![],
@@ -234,6 +519,13 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     )); 
+    entry_points.add_entry_point(EntryPoint::new(
+        "staking_ends",
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "withdraw_starts",
         vec![],
@@ -256,41 +548,257 @@ fn get_entry_points() -> EntryPoints {
         EntryPointType::Contract,
     )); 
     entry_points.add_entry_point(EntryPoint::new(
-        "transfer",
+        "amount_staked",
         vec![
-            Parameter::new("recipient", Key::cl_type()),
-            Parameter::new("amount", Key::cl_type())
+            Parameter::new("staker", Key::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "stake",
+        vec![
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("proof", Vec::<[u8; 32]>::cl_type()),
+            Parameter::new("allocation", U256::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw",
+        vec![
+            Parameter::new("amount", U256::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_reward",
+        vec![
+            Parameter::new("reward_amount", U256::cl_type()),
+            Parameter::new("withdrawable_amount", U256::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "min_stake",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "max_stake_per_address",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "max_stakers",
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_stake_limits",
+        vec![
+            Parameter::new("min_stake", U256::cl_type()),
+            Parameter::new("max_stake_per_address", U256::cl_type()),
+            Parameter::new("max_stakers", u64::cl_type())
         ],
         <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "transfer_from",
+        "penalty_bps",
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "penalty_decays",
+        vec![],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "penalty_treasury",
+        vec![],
+        Option::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_penalty",
         vec![
-            Parameter::new("sender", Key::cl_type()),
-            Parameter::new("recipient", Key::cl_type()),
-            Parameter::new("amount", Key::cl_type())
+            Parameter::new("penalty_bps", u64::cl_type()),
+            Parameter::new("decays", bool::cl_type()),
+            Parameter::new("treasury", Option::<Key>::cl_type())
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "owner",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "merkle_root",
+        vec![],
+        Option::<[u8; 32]>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "paused",
+        vec![],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_paused",
+        vec![
+            Parameter::new("paused", bool::cl_type())
         ],
         <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "approve",
+        "set_merkle_root",
         vec![
-            Parameter::new("spender", Key::cl_type())
+            Parameter::new("merkle_root", Option::<[u8; 32]>::cl_type())
         ],
         <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
     entry_points.add_entry_point(EntryPoint::new(
-        "get_approved",
+        "staker_count",
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "stakers",
         vec![
-            Parameter::new("owner", Key::cl_type())
+            Parameter::new("offset", u64::cl_type()),
+            Parameter::new("limit", u64::cl_type())
         ],
-        CLType::Option(Box::new(CLType::Key)),
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "staked_at",
+        vec![
+            Parameter::new("staker", Key::cl_type()),
+            Parameter::new("timestamp", u64::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_staked_at",
+        vec![
+            Parameter::new("timestamp", u64::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "current_phase",
+        vec![],
+        Phase::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "staker_info",
+        vec![
+            Parameter::new("staker", Key::cl_type())
+        ],
+        StakerInfo::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "preview_stake",
+        vec![
+            Parameter::new("amount", U256::cl_type())
+        ],
+        <(U256, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "preview_withdraw",
+        vec![
+            Parameter::new("staker", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type())
+        ],
+        <(U256, U256, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_reward",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "compound",
+        vec![
+            Parameter::new("proof", Vec::<[u8; 32]>::cl_type()),
+            Parameter::new("allocation", U256::cl_type())
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "finalize",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_refund",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "recover_token",
+        vec![
+            Parameter::new("token_hash", ContractHash::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type())
+        ],
+        <()>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
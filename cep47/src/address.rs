@@ -1,10 +1,15 @@
 //! Implementation of an `Address` which refers either an account hash, or a contract hash.
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
 use casper_types::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes},
+    key::FromStrError,
     CLType, CLTyped, ContractPackageHash, Key,
 };
+#[cfg(feature = "serde")]
+use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
 
 /// An enum representing an [`AccountHash`] or a [`ContractPackageHash`].
 #[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
@@ -33,6 +38,42 @@ impl Address {
             None
         }
     }
+
+    /// Formats `self` as `account-hash-...` or `hash-...`, the same as the equivalent [`Key`].
+    pub fn to_formatted_string(&self) -> String {
+        Key::from(*self).to_formatted_string()
+    }
+
+    /// Parses an `account-hash-...` or `hash-...` string into an `Address`.
+    pub fn from_formatted_str(input: &str) -> Result<Address, FromStrError> {
+        match Key::from_formatted_str(input)? {
+            Key::Account(account_hash) => Ok(Address::Account(account_hash)),
+            Key::Hash(hash) => Ok(Address::Contract(ContractPackageHash::new(hash))),
+            _ => Err(FromStrError::InvalidPrefix),
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_formatted_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_formatted_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let formatted = String::deserialize(deserializer)?;
+        Address::from_formatted_str(&formatted)
+            .map_err(|error| SerdeError::custom(format!("invalid address: {}", error)))
+    }
 }
 
 impl From<ContractPackageHash> for Address {
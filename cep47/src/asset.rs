@@ -0,0 +1,154 @@
+//! Implementation of [`StakingAsset`], the token interfaces a pool can stake and pay rewards in.
+//!
+//! Token standards on Casper disagree on entry point argument names and return types, so the pool
+//! never builds token call arguments itself and goes through the adapter selected at install.
+use alloc::{boxed::Box, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    runtime_args, CLType, CLTyped, ContractHash, Key, RuntimeArgs, U256,
+};
+
+use crate::{data, Error};
+
+/// A token the pool can hold, pay out and pull from approving owners.
+pub trait StakingAsset {
+    /// Returns the balance held by `owner`.
+    fn balance_of(&self, owner: Key) -> U256;
+
+    /// Pays `amount` out of the calling contract to `recipient`.
+    fn transfer(&self, recipient: Key, amount: U256) -> Result<(), Error>;
+
+    /// Moves `amount` from `owner`, who approved the calling contract, to `recipient`.
+    fn transfer_from(&self, owner: Key, recipient: Key, amount: U256) -> Result<(), Error>;
+}
+
+/// The token interface a pool was installed with.
+#[repr(u8)]
+#[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum AssetKind {
+    /// A CEP-18 fungible token.
+    Cep18 = 0,
+    /// Wrapped CSPR, whose transfers return `Result<(), u32>`.
+    WrappedCspr = 1,
+    /// A legacy Casper port of ERC-20, which shares the CEP-18 entry points.
+    Erc20 = 2,
+    /// Native CSPR, held in the pool's own purse rather than by a token contract.
+    Native = 3,
+}
+
+impl AssetKind {
+    /// Returns the adapter for a token of this kind deployed at `contract`. Native pools move
+    /// motes through their purse and have no adapter, so asking for one reverts with
+    /// `NotTokenPool`.
+    pub fn asset(&self, contract: ContractHash) -> Box<dyn StakingAsset> {
+        match self {
+            AssetKind::Cep18 | AssetKind::Erc20 => Box::new(Cep18 { contract }),
+            AssetKind::WrappedCspr => Box::new(WrappedCspr { contract }),
+            AssetKind::Native => runtime::revert(Error::NotTokenPool),
+        }
+    }
+}
+
+impl CLTyped for AssetKind {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for AssetKind {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for AssetKind {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let asset_kind = match tag {
+            0 => AssetKind::Cep18,
+            1 => AssetKind::WrappedCspr,
+            2 => AssetKind::Erc20,
+            3 => AssetKind::Native,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        Ok((asset_kind, remainder))
+    }
+}
+
+/// Calls `entry_point` on `contract` while holding the pool's reentrancy lock, so that the token
+/// can't call back into any state-changing entry point of the pool.
+fn call_locked<T: CLTyped + FromBytes>(
+    contract: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> T {
+    data::set_locked(true);
+    let ret = runtime::call_contract(contract, entry_point, args);
+    data::set_locked(false);
+    ret
+}
+
+/// A CEP-18 token, or a legacy ERC-20 port with the same entry points.
+pub struct Cep18 {
+    contract: ContractHash,
+}
+
+impl StakingAsset for Cep18 {
+    fn balance_of(&self, owner: Key) -> U256 {
+        call_locked(self.contract, "balance_of", runtime_args! { "address" => owner })
+    }
+
+    fn transfer(&self, recipient: Key, amount: U256) -> Result<(), Error> {
+        let args = runtime_args! {
+            "recipient" => recipient,
+            "amount" => amount
+        };
+        call_locked::<()>(self.contract, "transfer", args);
+        Ok(())
+    }
+
+    fn transfer_from(&self, owner: Key, recipient: Key, amount: U256) -> Result<(), Error> {
+        let args = runtime_args! {
+            "owner" => owner,
+            "recipient" => recipient,
+            "amount" => amount
+        };
+        call_locked::<()>(self.contract, "transfer_from", args);
+        Ok(())
+    }
+}
+
+/// Wrapped CSPR, which reports failed transfers through its return value instead of reverting.
+pub struct WrappedCspr {
+    contract: ContractHash,
+}
+
+impl StakingAsset for WrappedCspr {
+    fn balance_of(&self, owner: Key) -> U256 {
+        call_locked(self.contract, "balance_of", runtime_args! { "owner" => owner })
+    }
+
+    fn transfer(&self, recipient: Key, amount: U256) -> Result<(), Error> {
+        let args = runtime_args! {
+            "recipient" => recipient,
+            "amount" => amount
+        };
+        call_locked::<Result<(), u32>>(self.contract, "transfer", args)
+            .map_err(|_| Error::TransferFailed)
+    }
+
+    fn transfer_from(&self, owner: Key, recipient: Key, amount: U256) -> Result<(), Error> {
+        let args = runtime_args! {
+            "owner" => owner,
+            "recipient" => recipient,
+            "amount" => amount
+        };
+        call_locked::<Result<(), u32>>(self.contract, "transfer_from", args)
+            .map_err(|_| Error::TransferFailed)
+    }
+}
@@ -0,0 +1,52 @@
+//! Implementation of [`CallerPolicy`], which callers may hold a stake in the pool.
+use alloc::vec::Vec;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped,
+};
+
+/// Which callers of `stake` and `compound` are accepted, set at install. Anyone holding a stake
+/// can always withdraw and claim it.
+///
+/// Session and stored session code both run in the context of the account that sent the deploy,
+/// so the account is credited in either case. A stored contract is credited under its contract
+/// package hash.
+#[repr(u8)]
+#[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CallerPolicy {
+    /// Only accounts, through session or stored session code.
+    AccountsOnly = 0,
+    /// Accounts, and the contract packages the owner allowlisted.
+    AllowlistedContracts = 1,
+    /// Any account or contract.
+    Anyone = 2,
+}
+
+impl CLTyped for CallerPolicy {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for CallerPolicy {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for CallerPolicy {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let caller_policy = match tag {
+            0 => CallerPolicy::AccountsOnly,
+            1 => CallerPolicy::AllowlistedContracts,
+            2 => CallerPolicy::Anyone,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        Ok((caller_policy, remainder))
+    }
+}
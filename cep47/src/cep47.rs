@@ -1,15 +1,25 @@
 use crate::{
-    data::{self, StakedTokens},
-    event::CEP47Event
+    address::Address,
+    asset::{AssetKind, StakingAsset},
+    caller_policy::CallerPolicy,
+    data::{
+        self, AllowedContracts, Checkpoints, ClaimedRewards, CompoundedStakes, Funders, StakedTokens,
+        StakerInfos, Stakers
+    },
+    event::CEP47Event,
+    phase::Phase,
+    staker_info::StakerInfo
 };
-use casper_types::RuntimeArgs;
-use alloc::{string::String};
-use casper_types::{ApiError, Key, U256, BlockTime, runtime_args, ContractPackageHash};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use casper_types::{ApiError, Key, U256, URef};
 use contract_utils::{ContractContext, ContractStorage};
 // use core::convert::TryInto;
-use casper_contract::contract_api::runtime;
-use casper_types::ContractHash;
-use crate::detail;
+use casper_contract::{contract_api::{runtime, system}, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{ContractHash, ContractPackageHash};
+use crate::{detail, merkle, rewards};
+
+/// Highest early exit penalty, 20%.
+pub const MAX_PENALTY_BPS: u64 = 2_000;
 
 #[repr(u16)]
 pub enum Error {
@@ -19,7 +29,32 @@ pub enum Error {
     BadTiming = 4,
     InvalidContext = 5,
     NegativeReward =6,
-    NegativeWithdrawableReward = 7
+    NegativeWithdrawableReward = 7,
+    ExpectedStakingPhase = 8,
+    ExpectedWithdrawPhase = 9,
+    ExpectedFundingPhase = 10,
+    StakingCapFilled = 11,
+    InvalidProof = 12,
+    AllocationExceeded = 13,
+    BelowMinimumStake = 14,
+    AboveMaximumStake = 15,
+    TooManyStakers = 16,
+    ExpectedClosedPhase = 17,
+    StakesOutstanding = 18,
+    PoolFinalized = 19,
+    ExceedsSurplus = 20,
+    AccountRequired = 21,
+    Reentrancy = 22,
+    InsufficientStake = 23,
+    Overflow = 24,
+    InsufficientRewardBalance = 25,
+    TransferFailed = 26,
+    InvalidAddress = 27,
+    CallerNotAllowed = 28,
+    Paused = 29,
+    NotFinalized = 30,
+    ExpectedNotStartedPhase = 31,
+    NotTokenPool = 32
 }
 
 impl From<Error> for ApiError {
@@ -29,6 +64,7 @@ impl From<Error> for ApiError {
 }
 
 pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
+    #[allow(clippy::too_many_arguments)]
     fn init(&mut self,
         name: String,
         address: String, 
@@ -36,8 +72,31 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
         staking_ends: u64,
         withdraw_starts: u64,
         withdraw_ends: u64,
-        staking_total: U256
+        staking_total: U256,
+        min_stake: U256,
+        max_stake_per_address: U256,
+        max_stakers: u64,
+        asset_kind: AssetKind,
+        caller_policy: CallerPolicy
         ) {
+        if !(staking_starts < staking_ends
+            && staking_ends <= withdraw_starts
+            && withdraw_starts < withdraw_ends)
+        {
+            runtime::revert(Error::BadTiming);
+        }
+        // Native pools stake no token, any other pool must name a token contract.
+        let native = asset_kind == AssetKind::Native;
+        let address = if native {
+            None
+        } else {
+            // `hash-...` names the token's contract hash, which is what the pool calls it by,
+            // even though `Address` reads any hash as a contract package.
+            match Address::from_formatted_str(&address) {
+                Ok(Address::Contract(hash)) => Some(ContractHash::new(hash.value())),
+                _ => runtime::revert(Error::InvalidAddress),
+            }
+        };
         data::set_name(name);
         data::set_address(address);
         data::set_staking_starts(staking_starts);
@@ -45,17 +104,81 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
         data::set_withdraw_starts(withdraw_starts);
         data::set_withdraw_ends(withdraw_ends);
         data::set_staking_total(staking_total);
+        data::set_min_stake(min_stake);
+        data::set_max_stake_per_address(max_stake_per_address);
+        data::set_max_stakers(max_stakers);
+        data::set_asset_kind(asset_kind);
+        data::set_caller_policy(caller_policy);
+        data::set_locked(false);
+        data::set_paused(false);
+        if native {
+            data::set_pool_purse(system::create_purse());
+        }
+        data::set_owner(Key::from(detail::get_immediate_caller_address().unwrap_or_revert()));
+        data::set_merkle_root(None);
+        data::set_penalty_bps(0);
+        data::set_penalty_decays(false);
+        data::set_penalty_treasury(None);
+        data::set_staked_total(U256::zero());
+        data::set_compounded_total(U256::zero());
+        data::set_compounded_balance(U256::zero());
+        data::set_staked_balance(U256::zero());
+        data::set_total_reward(U256::zero());
+        data::set_reward_balance(U256::zero());
+        data::set_early_withdraw_reward(U256::zero());
+        data::set_total_claimed(U256::zero());
+        data::set_refund_pool(U256::zero());
+        data::set_refunded(U256::zero());
+        data::set_refunded_funding(U256::zero());
         StakedTokens::init();
+        ClaimedRewards::init();
+        CompoundedStakes::init();
+        Funders::init();
+        data::set_finalized(false);
+        StakerInfos::init();
+        Stakers::init();
+        Checkpoints::init();
+        AllowedContracts::init();
     }
 
     fn name(&self) -> String {
         data::name()
     }
 
-    fn address(&self) -> String {
+    fn address(&self) -> Option<ContractHash> {
         data::address()
     }
 
+    /// Returns `true` if the pool stakes native CSPR instead of a token.
+    fn native(&self) -> bool {
+        data::native()
+    }
+
+    fn asset_kind(&self) -> AssetKind {
+        data::asset_kind()
+    }
+
+    fn caller_policy(&self) -> CallerPolicy {
+        data::caller_policy()
+    }
+
+    fn is_allowed_contract(&self, contract_package_hash: ContractPackageHash) -> bool {
+        AllowedContracts::instance().contains(&contract_package_hash)
+    }
+
+    /// Adds or removes a contract package from those allowed to stake under
+    /// [`CallerPolicy::AllowlistedContracts`].
+    fn set_allowed_contract(
+        &mut self,
+        contract_package_hash: ContractPackageHash,
+        allowed: bool
+    ) -> Result<(), Error> {
+        self.only_owner()?;
+        self.require_not_finalized()?;
+        AllowedContracts::instance().set(&contract_package_hash, allowed);
+        Ok(())
+    }
+
     fn staking_starts(&self) -> u64 {
         data::staking_starts()
     }
@@ -77,42 +200,389 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn amount_staked(&self, staker: Key) -> U256 {
-        StakedTokens::instance().get_amount_staked_by_address(&staker).unwrap()
+        StakedTokens::instance().get_amount_staked_by_address(&staker)
+    }
+
+    fn min_stake(&self) -> U256 {
+        data::min_stake()
+    }
+
+    /// Maximum amount a single address may have staked, zero meaning unlimited.
+    fn max_stake_per_address(&self) -> U256 {
+        data::max_stake_per_address()
+    }
+
+    /// Maximum number of distinct stakers, zero meaning unlimited.
+    fn max_stakers(&self) -> u64 {
+        data::max_stakers()
+    }
+
+    fn set_stake_limits(
+        &mut self,
+        min_stake: U256,
+        max_stake_per_address: U256,
+        max_stakers: u64
+    ) -> Result<(), Error> {
+        self.only_owner()?;
+        self.require_not_finalized()?;
+        data::set_min_stake(min_stake);
+        data::set_max_stake_per_address(max_stake_per_address);
+        data::set_max_stakers(max_stakers);
+        Ok(())
+    }
+
+    /// Checks the per-address and per-pool limits for `staker` adding `amount`.
+    fn verify_stake_limits(&self, staker: Key, amount: U256) -> Result<(), Error> {
+        let staked = self.amount_staked(staker);
+        let max_stake_per_address = data::max_stake_per_address();
+        let total = staked.checked_add(amount).ok_or(Error::Overflow)?;
+        if !max_stake_per_address.is_zero() && total > max_stake_per_address {
+            return Err(Error::AboveMaximumStake);
         }
+        let max_stakers = data::max_stakers();
+        let registry = Stakers::instance();
+        if max_stakers != 0 && !registry.contains(&staker) && registry.count() >= max_stakers {
+            return Err(Error::TooManyStakers);
+        }
+        Ok(())
+    }
+
+    fn penalty_bps(&self) -> u64 {
+        data::penalty_bps()
+    }
 
+    fn penalty_decays(&self) -> bool {
+        data::penalty_decays()
+    }
+
+    fn penalty_treasury(&self) -> Option<Key> {
+        data::penalty_treasury()
+    }
+
+    /// Configures the penalty charged on principal withdrawn before `withdraw_ends`.
+    ///
+    /// With `decays` the rate falls linearly from `penalty_bps` at `withdraw_starts` to zero at
+    /// `withdraw_ends`. Penalties go to `treasury`, or to `reward_balance` for the remaining
+    /// stakers when it is `None`. Stakers enter under known terms, so the penalty can only be
+    /// changed before `staking_starts`, and never above `MAX_PENALTY_BPS`.
+    fn set_penalty(
+        &mut self,
+        penalty_bps: u64,
+        decays: bool,
+        treasury: Option<Key>
+    ) -> Result<(), Error> {
+        self.only_owner()?;
+        if self.current_phase() != Phase::NotStarted {
+            return Err(Error::ExpectedNotStartedPhase);
+        }
+        if penalty_bps > MAX_PENALTY_BPS {
+            return Err(Error::WrongArguments);
+        }
+        if let Some(treasury) = treasury {
+            self.require_payable(treasury)?;
+        }
+        data::set_penalty_bps(penalty_bps);
+        data::set_penalty_decays(decays);
+        data::set_penalty_treasury(treasury);
+        Ok(())
+    }
+
+    /// Penalty on withdrawing `amount` of principal during the early withdraw phase.
+    fn early_exit_penalty(&self, amount: U256) -> Result<U256, Error> {
+        rewards::early_exit_penalty(
+            amount,
+            data::penalty_bps(),
+            data::penalty_decays(),
+            self.withdraw_starts(),
+            self.withdraw_ends(),
+            detail::block_time(),
+        )
+        .ok_or(Error::Overflow)
+    }
+
+    fn owner(&self) -> Key {
+        data::owner()
+    }
+
+    fn merkle_root(&self) -> Option<[u8; 32]> {
+        data::merkle_root()
+    }
+
+    /// Sets the allowlist root, or opens the pool to anyone with `None`.
+    fn set_merkle_root(&mut self, merkle_root: Option<[u8; 32]>) -> Result<(), Error> {
+        self.only_owner()?;
+        self.require_not_finalized()?;
+        data::set_merkle_root(merkle_root);
+        Ok(())
+    }
+
+    fn paused(&self) -> bool {
+        data::paused()
+    }
+
+    /// Pauses or resumes deposits, claims and reward funding. Withdrawals stay open so that
+    /// stakers can always leave a paused pool.
+    fn set_paused(&mut self, paused: bool) -> Result<(), Error> {
+        self.only_owner()?;
+        self.require_not_finalized()?;
+        data::set_paused(paused);
+        Ok(())
+    }
+
+    fn require_not_paused(&self) -> Result<(), Error> {
+        if data::paused() {
+            return Err(Error::Paused);
+        }
+        Ok(())
+    }
+
+    fn require_not_finalized(&self) -> Result<(), Error> {
+        if data::finalized() {
+            return Err(Error::PoolFinalized);
+        }
+        Ok(())
+    }
+
+    fn only_owner(&self) -> Result<(), Error> {
+        if Key::from(detail::get_immediate_caller_address()?) != data::owner() {
+            return Err(Error::PermissionDenied);
+        }
+        Ok(())
+    }
+
+    /// Checks that `staker` is allowlisted for `allocation` and that its stake stays within it.
+    fn verify_allowlist(
+        &self,
+        staker: Key,
+        amount: U256,
+        proof: &[[u8; 32]],
+        allocation: U256
+    ) -> Result<(), Error> {
+        let root = match data::merkle_root() {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+        if !merkle::verify(proof, root, merkle::leaf(&staker, allocation)) {
+            return Err(Error::InvalidProof);
+        }
+        let staked = self.amount_staked(staker);
+        if staked.checked_add(amount).ok_or(Error::Overflow)? > allocation {
+            return Err(Error::AllocationExceeded);
+        }
+        Ok(())
+    }
+
+    fn staker_count(&self) -> u64 {
+        Stakers::instance().count()
+    }
+
+    /// Returns up to `limit` stakers starting at index `offset` of the registry.
+    fn stakers(&self, offset: u64, limit: u64) -> Vec<Key> {
+        let registry = Stakers::instance();
+        let end = core::cmp::min(offset.saturating_add(limit), registry.count());
+        (offset..end).filter_map(|index| registry.get(index)).collect()
+    }
+
+    fn staked_at(&self, staker: Key, timestamp: u64) -> U256 {
+        Checkpoints::staked().amount_at(&staker, timestamp)
+    }
+
+    fn total_staked_at(&self, timestamp: u64) -> U256 {
+        let pool = Key::Hash(data::contract_package_hash().value());
+        Checkpoints::total_staked().amount_at(&pool, timestamp)
+    }
+
+    /// Records the current amounts of `staker` and of the whole pool as checkpoints.
+    fn checkpoint(&self, staker: Key) {
+        let now = detail::block_time();
+        let pool = Key::Hash(data::contract_package_hash().value());
+        Checkpoints::staked().push(&staker, now, self.amount_staked(staker));
+        Checkpoints::total_staked().push(&pool, now, data::staked_balance());
+    }
+
+    fn current_phase(&self) -> Phase {
+        Phase::at(detail::block_time())
+    }
+
+    fn staker_info(&self, staker: Key) -> Result<StakerInfo, Error> {
+        let staked = self.amount_staked(staker);
+        let stored = StakerInfos::instance().get(&staker).unwrap_or_default();
+        let claimed = ClaimedRewards::instance().get(&staker);
+        Ok(StakerInfo {
+            staked,
+            pending_reward: self.accrued_reward(staker)?,
+            projected_reward: self
+                .after_close_reward(self.earning_share(staker, staked)?)?
+                .saturating_sub(claimed),
+            first_staked_at: stored.first_staked_at,
+            deposits: stored.deposits,
+        })
+    }
+
+    /// Reward for withdrawing `amount` before `withdraw_ends`, growing linearly from
+    /// `staking_ends` up to the share of `early_withdraw_reward` at close.
+    fn early_withdraw_reward(&self, amount: U256) -> Result<U256, Error> {
+        rewards::early_withdraw_reward(
+            amount,
+            data::staked_total(),
+            data::early_withdraw_reward(),
+            self.staking_ends(),
+            self.withdraw_ends(),
+            detail::block_time(),
+        )
+        .ok_or(Error::Overflow)
+    }
+
+    /// Reward for withdrawing `amount` after `withdraw_ends`, a pro-rata share of what is left in
+    /// `reward_balance` plus the claims stakers already took out of it. Compounded stakes don't
+    /// share in it, so `amount` is the earning part of a stake.
+    fn after_close_reward(&self, amount: U256) -> Result<U256, Error> {
+        let earning_balance = data::staked_balance()
+            .checked_sub(data::compounded_balance())
+            .ok_or(Error::Overflow)?;
+        rewards::after_close_reward(
+            amount,
+            earning_balance,
+            data::reward_balance(),
+            data::total_claimed(),
+        )
+        .ok_or(Error::Overflow)
+    }
+
+    /// Reward `staker` could claim now on its whole stake, net of what it already claimed.
+    fn accrued_reward(&self, staker: Key) -> Result<U256, Error> {
+        let earning = self.earning_share(staker, self.amount_staked(staker))?;
+        let entitled = match self.current_phase() {
+            Phase::EarlyWithdraw => self.early_withdraw_reward(earning)?,
+            Phase::Closed | Phase::Finalized => self.after_close_reward(earning)?,
+            _ => U256::zero(),
+        };
+        Ok(entitled.saturating_sub(ClaimedRewards::instance().get(&staker)))
+    }
+
+    /// Part of the rewards `staker` already claimed attributable to `amount` of its stake.
+    fn claimed_share(&self, staker: Key, amount: U256) -> Result<U256, Error> {
+        let claimed = ClaimedRewards::instance().get(&staker);
+        rewards::pro_rata(claimed, amount, self.amount_staked(staker)).ok_or(Error::Overflow)
+    }
+
+    /// Part of `amount` of `staker`'s stake that came from compounded rewards, taken pro rata.
+    fn compounded_share(&self, staker: Key, amount: U256) -> Result<U256, Error> {
+        let compounded = CompoundedStakes::instance().get(&staker);
+        rewards::pro_rata(compounded, amount, self.amount_staked(staker)).ok_or(Error::Overflow)
+    }
+
+    /// Part of `amount` of `staker`'s stake that earns rewards, i.e. what it deposited.
+    fn earning_share(&self, staker: Key, amount: U256) -> Result<U256, Error> {
+        Ok(amount - self.compounded_share(staker, amount)?)
+    }
+
+    /// Splits a requested stake into the accepted amount and the refund that stays with the
+    /// staker, given the remaining room under `staking_total`. The minimum applies to the
+    /// accepted amount, so a stake clipped below `min_stake` is rejected.
+    ///
+    /// The preview only knows the pool, not the staker, so it covers the cap and the minimum
+    /// alone. The allowlist, `max_stake_per_address` and `max_stakers` are checked by `stake`
+    /// and can still reject an amount the preview accepted.
+    fn preview_stake(&self, amount: U256) -> Result<(U256, U256), Error> {
+        if amount.is_zero() {
+            return Err(Error::NotRequiredStake);
+        }
+        if self.current_phase() != Phase::Staking {
+            return Err(Error::ExpectedStakingPhase);
+        }
+        let (accepted, refund) =
+            rewards::clip_to_cap(amount, data::staked_total(), self.staking_total());
+        if accepted.is_zero() {
+            return Err(Error::StakingCapFilled);
+        }
+        if accepted < data::min_stake() {
+            return Err(Error::BelowMinimumStake);
+        }
+        Ok((accepted, refund))
+    }
+
+    /// Returns the principal, reward and total payout `staker` would receive by withdrawing
+    /// `amount` now. Any early exit penalty is already deducted from the returned principal.
+    /// Withdrawals of nothing, and partial withdrawals below `min_stake`, are rejected. The
+    /// whole stake can always be withdrawn, so a balance below the minimum is never stuck.
+    fn preview_withdraw(&self, staker: Key, amount: U256) -> Result<(U256, U256, U256), Error> {
+        if amount.is_zero() {
+           return Err(Error::NotRequiredStake);
+        }
+        let phase = self.current_phase();
+        if !phase.accepts_withdrawals() {
+            return Err(Error::ExpectedWithdrawPhase);
+        }
+        let staked = self.amount_staked(staker);
+        if amount > staked {
+            return Err(Error::InsufficientStake);
+        }
+        if amount < data::min_stake() && amount != staked {
+            return Err(Error::NotRequiredStake);
+        }
+        let earning = self.earning_share(staker, amount)?;
+        let (reward, penalty) = if phase == Phase::EarlyWithdraw {
+            (self.early_withdraw_reward(earning)?, self.early_exit_penalty(amount)?)
+        } else {
+            (self.after_close_reward(earning)?, U256::zero())
+        };
+        let reward = reward.saturating_sub(self.claimed_share(staker, amount)?);
+        if reward > data::reward_balance() {
+            return Err(Error::InsufficientRewardBalance);
+        }
+        let principal = amount - penalty;
+        let payout = principal.checked_add(reward).ok_or(Error::Overflow)?;
+        Ok((principal, reward, payout))
+    }
+
+    fn update_staker_info(&self, staker: Key, deposited: bool) -> Result<(), Error> {
+        let mut staker_info = self.staker_info(staker)?;
+        if deposited {
+            if staker_info.deposits == 0 {
+                staker_info.first_staked_at = detail::block_time();
+            }
+            staker_info.deposits = staker_info.deposits.saturating_add(1);
+        }
+        StakerInfos::instance().set(&staker, staker_info);
+        Ok(())
+    }
 
 
     fn stake(
         &mut self,
-        amount: U256
+        amount: U256,
+        proof: Vec<[u8; 32]>,
+        allocation: U256,
+        purse: Option<URef>
     ) -> Result<U256, Error> {
+        self.require_unlocked()?;
+        self.require_not_paused()?;
+
+        let (amount, _refund) = self.preview_stake(amount)?;
+        let staker = Key::from(detail::get_staker_address()?);
+        self.require_payable(staker)?;
+        self.verify_allowlist(staker, amount, &proof, allocation)?;
+        self.verify_stake_limits(staker, amount)?;
 
-        if amount < U256::from(2) {
+        // Only what actually arrived is credited, so the transfer has to come first. The
+        // reentrancy lock held across it keeps the pool state out of the token's reach. A fee
+        // can take the received amount below the minimum, so the checks run again on it.
+        let amount = self.receive(amount, purse)?;
+        if amount.is_zero() {
             return Err(Error::NotRequiredStake);
-        } 
-        if runtime::get_blocktime() >= BlockTime::new(self.staking_starts()) {
-            return Err(Error::BadTiming);
         }
-
-        if runtime::get_blocktime() >= BlockTime::new(self.staking_ends()) {
-            return Err(Error::BadTiming);
+        if amount < data::min_stake() {
+            return Err(Error::BelowMinimumStake);
         }
+        self.verify_stake_limits(staker, amount)?;
 
         let stakers_dict = StakedTokens::instance();
-        let lower_contracthash =
-        "contract-c9a9e704604260416bf908cb6274e5d765b36164cf1fb9597a0df67ec4063bfa".to_lowercase();
-        let contract_hash = ContractHash::from_formatted_str(&lower_contracthash).unwrap();
-        
-        let lower_contractpackagehash = "hash-wasmc4929e7fcb71772c1cb39ebb702a70d036b0ad4f9caf420d3fd377f749dfdb17".to_lowercase();
-        let contract_package_hash = ContractPackageHash::from_formatted_str(&lower_contractpackagehash).unwrap(); 
-
-        let args = runtime_args! {
-            "owner" => detail::get_immediate_caller_address()?,
-            "recipient" => contract_package_hash,
-            "amount" => amount
-        };
-        runtime::call_contract::<String>(contract_hash,"transfer_from", args);
-        stakers_dict.add_stake(&Key::from(detail::get_immediate_caller_address()?), &amount);
+        stakers_dict.add_stake(&staker, &amount)?;
+        Stakers::instance().add(&staker);
+        self.add_staked(amount)?;
+        self.update_staker_info(staker, true)?;
+        self.checkpoint(staker);
 
         self.emit(CEP47Event::Stake {
             amount,
@@ -120,40 +590,146 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
         Ok(amount)
     }
 
-
+    /// Withdraws `amount` of the caller's stake and returns the total paid out.
+    ///
+    /// Unlike a plain unstake, the payout includes the reward earned on `amount`: the early
+    /// withdraw reward before `withdraw_ends` and a share of `reward_balance` after it, net of
+    /// what the staker already claimed, as in `Staking.sol`. Any early exit penalty is taken out
+    /// of the principal.
     fn withdraw(
         &mut self,
         amount: U256
     ) -> Result<U256, Error> {
+        self.require_unlocked()?;
 
-        if amount < U256::from(2) {
-           return Err(Error::NotRequiredStake);
-        } 
-        if runtime::get_blocktime() >= BlockTime::new(self.staking_starts()) {
-            return Err(Error::BadTiming);
+        let staker = Key::from(detail::get_immediate_caller_address()?);
+        let (principal, reward, payout) = self.preview_withdraw(staker, amount)?;
+        let penalty = amount - principal;
+        let settled_claim = self.claimed_share(staker, amount)?;
+        let settled_compound = self.compounded_share(staker, amount)?;
+
+        let stakers_dict = StakedTokens::instance();
+        stakers_dict.withdraw_stake(&staker, &amount)?;
+        let claimed_rewards = ClaimedRewards::instance();
+        claimed_rewards.set(&staker, claimed_rewards.get(&staker).saturating_sub(settled_claim));
+        data::set_total_claimed(data::total_claimed().saturating_sub(settled_claim));
+        let compounded_stakes = CompoundedStakes::instance();
+        compounded_stakes.set(
+            &staker,
+            compounded_stakes.get(&staker).saturating_sub(settled_compound),
+        );
+        data::set_compounded_balance(data::compounded_balance().saturating_sub(settled_compound));
+        if self.amount_staked(staker).is_zero() {
+            Stakers::instance().remove(&staker);
+        }
+        self.take_reward(reward)?;
+        let staked_balance = data::staked_balance()
+            .checked_sub(amount)
+            .ok_or(Error::InsufficientStake)?;
+        data::set_staked_balance(staked_balance);
+        let treasury = data::penalty_treasury();
+        if !penalty.is_zero() && treasury.is_none() {
+            self.add_reward_balance(penalty)?;
+        }
+        self.update_staker_info(staker, false)?;
+        self.checkpoint(staker);
+        let (released, release_recipient) = if data::finalized() {
+            self.release_unclaimable_reward()?
+        } else {
+            (U256::zero(), None)
+        };
+
+        self.send(staker, payout)?;
+        if let Some(treasury) = treasury.filter(|_| !penalty.is_zero()) {
+            self.send(treasury, penalty)?;
+        }
+        if let Some(recipient) = release_recipient {
+            self.send(recipient, released)?;
         }
 
-        if runtime::get_blocktime() >= BlockTime::new(self.staking_ends()) {
-            return Err(Error::BadTiming);
+        self.emit(CEP47Event::Withdraw {
+            amount,
+            reward,
+            penalty,
+        });
+        Ok(payout)
+    }
+
+    /// Pays the caller's accrued reward while leaving its stake in place.
+    fn claim_reward(&mut self) -> Result<U256, Error> {
+        self.require_unlocked()?;
+        self.require_not_paused()?;
+        if !self.current_phase().accepts_withdrawals() {
+            return Err(Error::ExpectedWithdrawPhase);
+        }
+        let staker = Key::from(detail::get_immediate_caller_address()?);
+        let reward = self.accrued_reward(staker)?;
+        if reward.is_zero() {
+            return Ok(reward);
         }
 
-        let stakers_dict = StakedTokens::instance();
-        let lower_contracthash =
-        "contract-c9a9e704604260416bf908cb6274e5d765b36164cf1fb9597a0df67ec4063bfa".to_lowercase();
-        let contract_hash = ContractHash::from_formatted_str(&lower_contracthash).unwrap();
+        self.record_claim(staker, reward)?;
+        self.update_staker_info(staker, false)?;
 
-        let lower_contractpackagehash = "hash-4929e7fcb71772c1cb39ebb702a70d036b0ad4f9caf420d3fd377f749dfdb17".to_lowercase();
-        let _contract_package_hash = ContractPackageHash::from_formatted_str(&lower_contractpackagehash).unwrap();
+        self.send(staker, reward)?;
 
-        let args = runtime_args! {
-            "recipient" => detail::get_immediate_caller_address()?,
-            "amount" => amount
-    
-        };
-        runtime::call_contract::<String>(contract_hash,"transfer", args);
-        stakers_dict.withdraw_stake(&Key::from(detail::get_immediate_caller_address()?), &amount);
+        self.emit(CEP47Event::ClaimReward {
+            amount: reward,
+        });
+        Ok(reward)
+    }
 
-        self.emit(CEP47Event::Stake {
+    /// Restakes the caller's accrued reward in place, up to the room left under `staking_total`.
+    ///
+    /// Rewards are paid in the stake token, so no transfer is needed: the compounded amount is
+    /// booked as claimed and added to the caller's stake. It is subject to the allowlist and the
+    /// stake limits like any deposit, and counts against the cap through `compounded_total`.
+    ///
+    /// The compounded amount is principal from then on but earns no further reward. It is kept
+    /// in `compounded_stakes`, out of `staked_total` and out of the balance that shares
+    /// `reward_balance` after close, so compounding never dilutes the other stakers' rewards.
+    fn compound(&mut self, proof: Vec<[u8; 32]>, allocation: U256) -> Result<U256, Error> {
+        self.require_unlocked()?;
+        self.require_not_paused()?;
+        self.require_not_finalized()?;
+        if !self.current_phase().accepts_withdrawals() {
+            return Err(Error::ExpectedWithdrawPhase);
+        }
+        let staker = Key::from(detail::get_staker_address()?);
+        let filled = data::staked_total()
+            .checked_add(data::compounded_total())
+            .ok_or(Error::Overflow)?;
+        if filled >= self.staking_total() {
+            return Err(Error::StakingCapFilled);
+        }
+        let (amount, _) =
+            rewards::clip_to_cap(self.accrued_reward(staker)?, filled, self.staking_total());
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        self.verify_allowlist(staker, amount, &proof, allocation)?;
+        self.verify_stake_limits(staker, amount)?;
+
+        self.record_claim(staker, amount)?;
+        let compounded_stakes = CompoundedStakes::instance();
+        let compounded = compounded_stakes
+            .get(&staker)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        StakedTokens::instance().add_stake(&staker, &amount)?;
+        compounded_stakes.set(&staker, compounded);
+        let compounded_total = data::compounded_total().checked_add(amount).ok_or(Error::Overflow)?;
+        let compounded_balance = data::compounded_balance()
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        let staked_balance = data::staked_balance().checked_add(amount).ok_or(Error::Overflow)?;
+        data::set_compounded_total(compounded_total);
+        data::set_compounded_balance(compounded_balance);
+        data::set_staked_balance(staked_balance);
+        self.update_staker_info(staker, false)?;
+        self.checkpoint(staker);
+
+        self.emit(CEP47Event::Compound {
             amount,
         });
         Ok(amount)
@@ -162,10 +738,13 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
     fn add_reward(
         &mut self,
         reward_amount: U256,
-        withdrawable_amount: U256
+        withdrawable_amount: U256,
+        purse: Option<URef>
     ) -> Result<U256, Error> {
-        if runtime::get_blocktime() >= BlockTime::new(self.withdraw_starts()) {
-            return Err(Error::PermissionDenied)
+        self.require_unlocked()?;
+        self.require_not_paused()?;
+        if !self.current_phase().accepts_rewards() {
+            return Err(Error::ExpectedFundingPhase)
         }
 
         if reward_amount <= U256::from(0) {
@@ -179,21 +758,26 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
         if withdrawable_amount > reward_amount {
             return Err(Error::NegativeWithdrawableReward)
         }
+        let funder = Key::from(detail::get_immediate_caller_address()?);
+        self.require_payable(funder)?;
 
-        let lower_contracthash =
-        "contract-c9a9e704604260416bf908cb6274e5d765b36164cf1fb9597a0df67ec4063bfa".to_lowercase();
-        let contract_hash = ContractHash::from_formatted_str(&lower_contracthash).unwrap();
-        
-        let lower_contractpackagehash = "hash-wasmc4929e7fcb71772c1cb39ebb702a70d036b0ad4f9caf420d3fd377f749dfdb17".to_lowercase();
-        let contract_package_hash = ContractPackageHash::from_formatted_str(&lower_contractpackagehash).unwrap(); 
+        // As in `stake`, only the measured amount counts as funded.
+        let reward_amount = self.receive(reward_amount, purse)?;
+        let withdrawable_amount = core::cmp::min(withdrawable_amount, reward_amount);
+        if reward_amount.is_zero() {
+            return Err(Error::NegativeReward);
+        }
 
-        let args = runtime_args! {
-            "owner" => detail::get_immediate_caller_address()?,
-            "recipient" => contract_package_hash,
-            "amount" => reward_amount + withdrawable_amount
-    
-        };
-        runtime::call_contract::<String>(contract_hash,"transfer_from", args);
+        Funders::instance().add_funding(&funder, reward_amount)?;
+        let total_reward = data::total_reward()
+            .checked_add(reward_amount)
+            .ok_or(Error::Overflow)?;
+        let early_withdraw_reward = data::early_withdraw_reward()
+            .checked_add(withdrawable_amount)
+            .ok_or(Error::Overflow)?;
+        data::set_total_reward(total_reward);
+        data::set_reward_balance(total_reward);
+        data::set_early_withdraw_reward(early_withdraw_reward);
 
         self.emit(CEP47Event::AddReward
              {
@@ -204,6 +788,239 @@ pub trait CEP20STK<Storage: ContractStorage>: ContractContext<Storage> {
     }
     
 
+    /// Locks the pool after close and sets the reward no stake can claim aside for the funders,
+    /// who each take their share pro rata to what they funded with `claim_refund`. Without
+    /// funders, the reward balance only holds penalties and goes to the penalty treasury, or to
+    /// the owner.
+    ///
+    /// Stakes still in the pool keep their principal and the whole reward balance, which after
+    /// close belongs to them, and can be withdrawn as before. Whatever rounding leaves behind
+    /// once the last of them is withdrawn is released the same way.
+    fn finalize(&mut self) -> Result<U256, Error> {
+        self.require_unlocked()?;
+        match self.current_phase() {
+            Phase::Closed => {}
+            Phase::Finalized => return Err(Error::PoolFinalized),
+            _ => return Err(Error::ExpectedClosedPhase),
+        }
+
+        data::set_finalized(true);
+        let (returned_reward, recipient) = self.release_unclaimable_reward()?;
+        if let Some(recipient) = recipient {
+            self.send(recipient, returned_reward)?;
+        }
+
+        self.emit(CEP47Event::Finalize {
+            returned_reward,
+        });
+        Ok(returned_reward)
+    }
+
+    /// Pays the caller its share of the reward set aside by `finalize`, pro rata to what it
+    /// funded. Each funder is refunded once.
+    ///
+    /// Refunds are cut from the running total of funding refunded so far, so rounding never
+    /// strands anything: the last funder to claim takes what is left of the refund pool.
+    fn claim_refund(&mut self) -> Result<U256, Error> {
+        self.require_unlocked()?;
+        if !data::finalized() {
+            return Err(Error::NotFinalized);
+        }
+        let funder = Key::from(detail::get_immediate_caller_address()?);
+        let funded_amount = Funders::instance().take_funding(&funder);
+        if funded_amount.is_zero() {
+            return Ok(funded_amount);
+        }
+        let refunded_funding = data::refunded_funding()
+            .checked_add(funded_amount)
+            .ok_or(Error::Overflow)?;
+        let refunded = data::refund_pool()
+            .checked_mul(refunded_funding)
+            .ok_or(Error::Overflow)?
+            / data::total_reward();
+        let refund = refunded.saturating_sub(data::refunded());
+        data::set_refunded_funding(refunded_funding);
+        if refund.is_zero() {
+            return Ok(refund);
+        }
+        data::set_refunded(refunded);
+
+        self.send(funder, refund)?;
+
+        self.emit(CEP47Event::ClaimRefund {
+            funder,
+            amount: refund,
+        });
+        Ok(refund)
+    }
+
+    /// Sends `amount` of a token mistakenly transferred to the pool to `recipient`.
+    ///
+    /// For the pool's own token only the surplus above `staked_balance + reward_balance` and the
+    /// refunds funders have yet to claim can be recovered. Native pools hold no token of their own, so any token can be recovered in full.
+    /// Foreign tokens are assumed to be CEP-18.
+    fn recover_token(
+        &mut self,
+        token_hash: ContractHash,
+        recipient: Key,
+        amount: U256
+    ) -> Result<(), Error> {
+        self.require_unlocked()?;
+        self.only_owner()?;
+
+        let own_token = !data::native() && token_hash == Self::token_contract_hash();
+        let asset = if own_token {
+            Self::asset()
+        } else {
+            AssetKind::Cep18.asset(token_hash)
+        };
+        if own_token {
+            let balance = asset.balance_of(Key::Hash(data::contract_package_hash().value()));
+            let reserved = data::staked_balance()
+                .checked_add(data::reward_balance())
+                .and_then(|reserved| {
+                    reserved.checked_add(data::refund_pool().saturating_sub(data::refunded()))
+                })
+                .ok_or(Error::Overflow)?;
+            let surplus = balance.saturating_sub(reserved);
+            if amount > surplus {
+                return Err(Error::ExceedsSurplus);
+            }
+        }
+
+        asset.transfer(recipient, amount)?;
+
+        self.emit(CEP47Event::RecoverToken {
+            token: token_hash,
+            recipient,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Adds `amount` to both `staked_total` and `staked_balance`.
+    fn add_staked(&self, amount: U256) -> Result<(), Error> {
+        let staked_total = data::staked_total().checked_add(amount).ok_or(Error::Overflow)?;
+        let staked_balance = data::staked_balance().checked_add(amount).ok_or(Error::Overflow)?;
+        data::set_staked_total(staked_total);
+        data::set_staked_balance(staked_balance);
+        Ok(())
+    }
+
+    fn add_reward_balance(&self, amount: U256) -> Result<(), Error> {
+        let reward_balance = data::reward_balance().checked_add(amount).ok_or(Error::Overflow)?;
+        data::set_reward_balance(reward_balance);
+        Ok(())
+    }
+
+    /// Moves the part of `reward_balance` no stake can claim any more to the funders' refund
+    /// pool. Without funders it is returned with the penalty treasury, or the owner, as the
+    /// recipient to pay. After close the whole balance belongs to the stakes still earning, so
+    /// nothing is released while any are left.
+    fn release_unclaimable_reward(&self) -> Result<(U256, Option<Key>), Error> {
+        let earning_balance = data::staked_balance()
+            .checked_sub(data::compounded_balance())
+            .ok_or(Error::Overflow)?;
+        let released = data::reward_balance();
+        if !earning_balance.is_zero() || released.is_zero() {
+            return Ok((U256::zero(), None));
+        }
+        data::set_reward_balance(U256::zero());
+        if Funders::instance().count() == 0 {
+            return Ok((released, Some(data::penalty_treasury().unwrap_or_else(data::owner))));
+        }
+        let refund_pool = data::refund_pool().checked_add(released).ok_or(Error::Overflow)?;
+        data::set_refund_pool(refund_pool);
+        Ok((released, None))
+    }
+
+    /// Takes `amount` out of `reward_balance` for a payout.
+    fn take_reward(&self, amount: U256) -> Result<(), Error> {
+        let reward_balance = data::reward_balance()
+            .checked_sub(amount)
+            .ok_or(Error::InsufficientRewardBalance)?;
+        data::set_reward_balance(reward_balance);
+        Ok(())
+    }
+
+    /// Books `amount` as claimed by `staker` and takes it out of `reward_balance`.
+    fn record_claim(&self, staker: Key, amount: U256) -> Result<(), Error> {
+        let claimed_rewards = ClaimedRewards::instance();
+        let claimed = claimed_rewards
+            .get(&staker)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        let total_claimed = data::total_claimed().checked_add(amount).ok_or(Error::Overflow)?;
+        self.take_reward(amount)?;
+        claimed_rewards.set(&staker, claimed);
+        data::set_total_claimed(total_claimed);
+        Ok(())
+    }
+
+    /// Returns the token contract set at install.
+    fn token_contract_hash() -> ContractHash {
+        data::address().unwrap_or_revert_with(Error::InvalidAddress)
+    }
+
+    /// Returns the adapter of the pool's token, holding the reentrancy lock around every call.
+    fn asset() -> Box<dyn StakingAsset> {
+        data::asset_kind().asset(Self::token_contract_hash())
+    }
+
+    fn require_unlocked(&self) -> Result<(), Error> {
+        if data::locked() {
+            return Err(Error::Reentrancy);
+        }
+        Ok(())
+    }
+
+    /// Native pools can only pay out to accounts, so anyone the pool will owe must be one.
+    fn require_payable(&self, recipient: Key) -> Result<(), Error> {
+        if data::native() && recipient.into_account().is_none() {
+            return Err(Error::AccountRequired);
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` from the caller into the pool, out of `purse` in native mode or through
+    /// the token's `transfer_from` otherwise, and returns the amount received.
+    ///
+    /// Tokens may charge a fee on transfer, so in token mode the amount received is measured as
+    /// the change in the pool's balance rather than taken from `amount`.
+    fn receive(&self, amount: U256, purse: Option<URef>) -> Result<U256, Error> {
+        if data::native() {
+            let purse = purse.ok_or(Error::WrongArguments)?;
+            system::transfer_from_purse_to_purse(
+                purse,
+                data::pool_purse(),
+                detail::u256_to_u512(amount),
+                None
+            ).unwrap_or_revert();
+            return Ok(amount);
+        }
+        let asset = Self::asset();
+        let pool = Key::Hash(data::contract_package_hash().value());
+        let balance_before = asset.balance_of(pool);
+        let owner = Key::from(detail::get_immediate_caller_address()?);
+        asset.transfer_from(owner, pool, amount)?;
+        Ok(asset.balance_of(pool).saturating_sub(balance_before))
+    }
+
+    /// Pays `amount` out of the pool to `recipient`, which must be an account in native mode.
+    fn send(&self, recipient: Key, amount: U256) -> Result<(), Error> {
+        if data::native() {
+            let account_hash = recipient.into_account().ok_or(Error::AccountRequired)?;
+            system::transfer_from_purse_to_account(
+                data::pool_purse(),
+                account_hash,
+                detail::u256_to_u512(amount),
+                None
+            ).unwrap_or_revert();
+            return Ok(());
+        }
+        Self::asset().transfer(recipient, amount)
+    }
+
     fn emit(&mut self, event: CEP47Event) {
         data::emit(&event);
     }
@@ -4,25 +4,66 @@ use alloc::{
     vec::Vec,
 };
 use casper_contract::{
-    contract_api::{runtime::get_call_stack, storage},
+    contract_api::{runtime::{self, get_call_stack}, storage},
     unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_types::{system::CallStackElement, ContractPackageHash, Key, URef, U256};
-use contract_utils::{get_key, key_to_str, set_key, Dict};
+use casper_types::{system::CallStackElement, ContractHash, ContractPackageHash, Key, URef, U256};
+use contract_utils::{get_key, key_and_value_to_str, key_to_str, set_key, Dict};
 use crate::detail;
-use crate::{event::CEP47Event};
+use crate::{
+    asset::AssetKind, caller_policy::CallerPolicy, event::CEP47Event, staker_info::StakerInfo,
+    Error,
+};
 
 const STAKERS_DICT: &str = "stakers";
 const AMOUNT_STAKED_BY_ADDRESS_DICT: &str = "amount_staked_by_addresses_dict";
+const STAKER_INFO_DICT: &str = "staker_info";
+const STAKER_INDEXES_DICT: &str = "staker_indexes";
+const CLAIMED_REWARDS_DICT: &str = "claimed_rewards";
+const COMPOUNDED_STAKES_DICT: &str = "compounded_stakes";
+const FUNDERS_DICT: &str = "funders";
+const FUNDED_AMOUNTS_DICT: &str = "funded_amounts";
+const ALLOWED_CONTRACTS_DICT: &str = "allowed_contracts";
+const STAKED_CHECKPOINTS_DICT: &str = "staked_checkpoints";
+const STAKED_CHECKPOINT_COUNTS_DICT: &str = "staked_checkpoint_counts";
+const TOTAL_STAKED_CHECKPOINTS_DICT: &str = "total_staked_checkpoints";
+const TOTAL_STAKED_CHECKPOINT_COUNTS_DICT: &str = "total_staked_checkpoint_counts";
 const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
 
-pub const NAME: &str = "address";
+pub const NAME: &str = "name";
 pub const ADDRESS: &str = "address";
 pub const STAKING_STARTS: &str = "staking_starts";
 pub const STAKING_ENDS: &str = "staking_ends";
 pub const WITHDRAW_STARTS: &str = "withdraw_starts";
 pub const WITHDRAW_ENDS: &str = "withdraw_ends";
 pub const STAKING_TOTAL: &str = "staking_total";
+pub const STAKED_TOTAL: &str = "staked_total";
+pub const COMPOUNDED_TOTAL: &str = "compounded_total";
+pub const COMPOUNDED_BALANCE: &str = "compounded_balance";
+pub const STAKED_BALANCE: &str = "staked_balance";
+pub const TOTAL_REWARD: &str = "total_reward";
+pub const REWARD_BALANCE: &str = "reward_balance";
+pub const EARLY_WITHDRAW_REWARD: &str = "early_withdraw_reward";
+pub const FINALIZED: &str = "finalized";
+pub const STAKER_COUNT: &str = "staker_count";
+pub const OWNER: &str = "owner";
+pub const MERKLE_ROOT: &str = "merkle_root";
+pub const MIN_STAKE: &str = "min_stake";
+pub const MAX_STAKE_PER_ADDRESS: &str = "max_stake_per_address";
+pub const MAX_STAKERS: &str = "max_stakers";
+pub const TOTAL_CLAIMED: &str = "total_claimed";
+pub const FUNDER_COUNT: &str = "funder_count";
+pub const REFUND_POOL: &str = "refund_pool";
+pub const REFUNDED: &str = "refunded";
+pub const REFUNDED_FUNDING: &str = "refunded_funding";
+pub const LOCKED: &str = "locked";
+pub const POOL_PURSE: &str = "pool_purse";
+pub const PENALTY_BPS: &str = "penalty_bps";
+pub const PENALTY_DECAYS: &str = "penalty_decays";
+pub const PENALTY_TREASURY: &str = "penalty_treasury";
+pub const ASSET_KIND: &str = "asset_kind";
+pub const CALLER_POLICY: &str = "caller_policy";
+pub const PAUSED: &str = "paused";
 
 
 
@@ -43,23 +84,333 @@ impl StakedTokens {
         Dict::init(AMOUNT_STAKED_BY_ADDRESS_DICT);
     }
 
-    pub fn get_amount_staked_by_address(&self, address: &Key) -> Option<U256> {
-        self.addresses_staked_dict.get(&key_to_str(address))
+    /// Returns the amount `address` has staked, zero if it never staked.
+    pub fn get_amount_staked_by_address(&self, address: &Key) -> U256 {
+        self.addresses_staked_dict
+            .get(&key_to_str(address))
+            .unwrap_or_default()
     }
 
-
-    pub fn add_stake(&self, owner: &Key, amount: &U256) {
-        let staked_amount = self.get_amount_staked_by_address(owner).unwrap();
-        let new_amount = staked_amount + amount;
+    pub fn add_stake(&self, owner: &Key, amount: &U256) -> Result<(), Error> {
+        let new_amount = self
+            .get_amount_staked_by_address(owner)
+            .checked_add(*amount)
+            .ok_or(Error::Overflow)?;
         self.addresses_staked_dict
-            .set(&key_to_str(owner),new_amount);
+            .set(&key_to_str(owner), new_amount);
+        Ok(())
     }
 
-    pub fn withdraw_stake(&self, owner: &Key, amount: &U256) {
-        let staked_amount = self.get_amount_staked_by_address(owner).unwrap();
-        let new_amount = staked_amount - amount;
+    pub fn withdraw_stake(&self, owner: &Key, amount: &U256) -> Result<(), Error> {
+        let new_amount = self
+            .get_amount_staked_by_address(owner)
+            .checked_sub(*amount)
+            .ok_or(Error::InsufficientStake)?;
         self.addresses_staked_dict
-            .set(&key_to_str(owner),new_amount);
+            .set(&key_to_str(owner), new_amount);
+        Ok(())
+    }
+}
+
+pub struct StakerInfos {
+    staker_info_dict: Dict,
+}
+
+impl StakerInfos {
+    pub fn instance() -> StakerInfos {
+        StakerInfos {
+            staker_info_dict: Dict::instance(STAKER_INFO_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(STAKER_INFO_DICT);
+    }
+
+    pub fn get(&self, staker: &Key) -> Option<StakerInfo> {
+        self.staker_info_dict.get(&key_to_str(staker))
+    }
+
+    pub fn set(&self, staker: &Key, staker_info: StakerInfo) {
+        self.staker_info_dict.set(&key_to_str(staker), staker_info);
+    }
+}
+
+pub struct Stakers {
+    stakers_dict: Dict,
+    staker_indexes_dict: Dict,
+}
+
+impl Stakers {
+    pub fn instance() -> Stakers {
+        Stakers {
+            stakers_dict: Dict::instance(STAKERS_DICT),
+            staker_indexes_dict: Dict::instance(STAKER_INDEXES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(STAKERS_DICT);
+        Dict::init(STAKER_INDEXES_DICT);
+        set_key(STAKER_COUNT, 0u64);
+    }
+
+    pub fn count(&self) -> u64 {
+        get_key(STAKER_COUNT).unwrap_or_default()
+    }
+
+    pub fn get(&self, index: u64) -> Option<Key> {
+        self.stakers_dict.get(&index.to_string())
+    }
+
+    pub fn contains(&self, staker: &Key) -> bool {
+        self.staker_indexes_dict
+            .get::<u64>(&key_to_str(staker))
+            .is_some()
+    }
+
+    pub fn add(&self, staker: &Key) {
+        if self.contains(staker) {
+            return;
+        }
+        let count = self.count();
+        self.stakers_dict.set(&count.to_string(), *staker);
+        self.staker_indexes_dict.set(&key_to_str(staker), count);
+        set_key(STAKER_COUNT, count + 1);
+    }
+
+    /// Removes `staker` by moving the last staker into its slot.
+    pub fn remove(&self, staker: &Key) {
+        let index: u64 = match self.staker_indexes_dict.get(&key_to_str(staker)) {
+            Some(index) => index,
+            None => return,
+        };
+        let last_index = self.count() - 1;
+        if index != last_index {
+            let last_staker: Key = self.get(last_index).unwrap_or_revert();
+            self.stakers_dict.set(&index.to_string(), last_staker);
+            self.staker_indexes_dict.set(&key_to_str(&last_staker), index);
+        }
+        self.stakers_dict.remove::<Key>(&last_index.to_string());
+        self.staker_indexes_dict.remove::<u64>(&key_to_str(staker));
+        set_key(STAKER_COUNT, last_index);
+    }
+}
+
+/// Accounts that funded rewards through `add_reward`, with the amount each contributed.
+pub struct Funders {
+    funders_dict: Dict,
+    funded_amounts_dict: Dict,
+}
+
+impl Funders {
+    pub fn instance() -> Funders {
+        Funders {
+            funders_dict: Dict::instance(FUNDERS_DICT),
+            funded_amounts_dict: Dict::instance(FUNDED_AMOUNTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(FUNDERS_DICT);
+        Dict::init(FUNDED_AMOUNTS_DICT);
+        set_key(FUNDER_COUNT, 0u64);
+    }
+
+    pub fn count(&self) -> u64 {
+        get_key(FUNDER_COUNT).unwrap_or_default()
+    }
+
+    pub fn get(&self, index: u64) -> Option<Key> {
+        self.funders_dict.get(&index.to_string())
+    }
+
+    pub fn funded_amount(&self, funder: &Key) -> U256 {
+        self.funded_amounts_dict
+            .get(&key_to_str(funder))
+            .unwrap_or_default()
+    }
+
+    pub fn add_funding(&self, funder: &Key, amount: U256) -> Result<(), Error> {
+        let funded_amount: Option<U256> = self.funded_amounts_dict.get(&key_to_str(funder));
+        let new_amount = funded_amount
+            .unwrap_or_default()
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        if funded_amount.is_none() {
+            let count = self.count();
+            self.funders_dict.set(&count.to_string(), *funder);
+            set_key(FUNDER_COUNT, count + 1);
+        }
+        self.funded_amounts_dict.set(&key_to_str(funder), new_amount);
+        Ok(())
+    }
+
+    /// Returns what `funder` funded and zeroes it, so its refund is only paid once.
+    pub fn take_funding(&self, funder: &Key) -> U256 {
+        let funded_amount = self.funded_amount(funder);
+        if !funded_amount.is_zero() {
+            self.funded_amounts_dict.set(&key_to_str(funder), U256::zero());
+        }
+        funded_amount
+    }
+}
+
+/// Contract packages allowed to stake under [`CallerPolicy::AllowlistedContracts`].
+pub struct AllowedContracts {
+    allowed_contracts_dict: Dict,
+}
+
+impl AllowedContracts {
+    pub fn instance() -> AllowedContracts {
+        AllowedContracts {
+            allowed_contracts_dict: Dict::instance(ALLOWED_CONTRACTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ALLOWED_CONTRACTS_DICT);
+    }
+
+    pub fn contains(&self, contract_package_hash: &ContractPackageHash) -> bool {
+        self.allowed_contracts_dict
+            .get(&key_to_str(&Key::from(*contract_package_hash)))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, contract_package_hash: &ContractPackageHash, allowed: bool) {
+        self.allowed_contracts_dict
+            .set(&key_to_str(&Key::from(*contract_package_hash)), allowed);
+    }
+}
+
+/// Rewards claimed by each staker and not yet settled by a withdrawal.
+pub struct ClaimedRewards {
+    claimed_rewards_dict: Dict,
+}
+
+impl ClaimedRewards {
+    pub fn instance() -> ClaimedRewards {
+        ClaimedRewards {
+            claimed_rewards_dict: Dict::instance(CLAIMED_REWARDS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(CLAIMED_REWARDS_DICT);
+    }
+
+    pub fn get(&self, staker: &Key) -> U256 {
+        self.claimed_rewards_dict
+            .get(&key_to_str(staker))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, staker: &Key, claimed: U256) {
+        self.claimed_rewards_dict.set(&key_to_str(staker), claimed);
+    }
+}
+
+/// Part of each staker's stake that came from compounded rewards.
+pub struct CompoundedStakes {
+    compounded_stakes_dict: Dict,
+}
+
+impl CompoundedStakes {
+    pub fn instance() -> CompoundedStakes {
+        CompoundedStakes {
+            compounded_stakes_dict: Dict::instance(COMPOUNDED_STAKES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(COMPOUNDED_STAKES_DICT);
+    }
+
+    pub fn get(&self, staker: &Key) -> U256 {
+        self.compounded_stakes_dict
+            .get(&key_to_str(staker))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, staker: &Key, compounded: U256) {
+        self.compounded_stakes_dict.set(&key_to_str(staker), compounded);
+    }
+}
+
+/// Append-only history of `(block time, amount)` pairs per owner, ordered by block time.
+pub struct Checkpoints {
+    checkpoints_dict: Dict,
+    counts_dict: Dict,
+}
+
+impl Checkpoints {
+    /// Checkpoints of each staker's amount staked.
+    pub fn staked() -> Checkpoints {
+        Checkpoints {
+            checkpoints_dict: Dict::instance(STAKED_CHECKPOINTS_DICT),
+            counts_dict: Dict::instance(STAKED_CHECKPOINT_COUNTS_DICT),
+        }
+    }
+
+    /// Checkpoints of the pool's staked balance, owned by the contract package.
+    pub fn total_staked() -> Checkpoints {
+        Checkpoints {
+            checkpoints_dict: Dict::instance(TOTAL_STAKED_CHECKPOINTS_DICT),
+            counts_dict: Dict::instance(TOTAL_STAKED_CHECKPOINT_COUNTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(STAKED_CHECKPOINTS_DICT);
+        Dict::init(STAKED_CHECKPOINT_COUNTS_DICT);
+        Dict::init(TOTAL_STAKED_CHECKPOINTS_DICT);
+        Dict::init(TOTAL_STAKED_CHECKPOINT_COUNTS_DICT);
+    }
+
+    pub fn count(&self, owner: &Key) -> u64 {
+        self.counts_dict.get(&key_to_str(owner)).unwrap_or_default()
+    }
+
+    pub fn get(&self, owner: &Key, index: u64) -> Option<(u64, U256)> {
+        self.checkpoints_dict.get(&key_and_value_to_str(owner, &index))
+    }
+
+    /// Records `amount` at block time `time`, overwriting the last checkpoint if it has the same
+    /// block time.
+    pub fn push(&self, owner: &Key, time: u64, amount: U256) {
+        let count = self.count(owner);
+        if count > 0 {
+            let (last_time, _) = self.get(owner, count - 1).unwrap_or_revert();
+            if last_time == time {
+                self.checkpoints_dict
+                    .set(&key_and_value_to_str(owner, &(count - 1)), (time, amount));
+                return;
+            }
+        }
+        self.checkpoints_dict
+            .set(&key_and_value_to_str(owner, &count), (time, amount));
+        self.counts_dict.set(&key_to_str(owner), count + 1);
+    }
+
+    /// Returns the amount recorded by the last checkpoint at or before `time`, or zero.
+    pub fn amount_at(&self, owner: &Key, time: u64) -> U256 {
+        let mut low = 0;
+        let mut high = self.count(owner);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (checkpoint_time, _) = self.get(owner, mid).unwrap_or_revert();
+            if checkpoint_time > time {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        if low == 0 {
+            return U256::zero();
+        }
+        let (_, amount) = self.get(owner, low - 1).unwrap_or_revert();
+        amount
     }
 }
 
@@ -71,11 +422,12 @@ pub fn set_name(name: String) {
     set_key(NAME, name);
 }
 
-pub fn address() -> String {
+/// Returns the token contract staked by the pool, `None` for native pools.
+pub fn address() -> Option<ContractHash> {
     get_key(ADDRESS).unwrap_or_revert()
 }
 
-pub fn set_address(address: String) {
+pub fn set_address(address: Option<ContractHash>) {
     set_key(ADDRESS, address);
 }
 
@@ -108,7 +460,7 @@ pub fn withdraw_ends() -> u64 {
 }
 
 pub fn set_withdraw_ends(withdraw_ends: u64) {
-    set_key(WITHDRAW_STARTS, withdraw_ends);
+    set_key(WITHDRAW_ENDS, withdraw_ends);
 }
 
 pub fn staking_total() -> U256 {
@@ -119,6 +471,216 @@ pub fn set_staking_total(staking_total: U256) {
     set_key(STAKING_TOTAL, staking_total);
 }
 
+pub fn staked_total() -> U256 {
+    get_key(STAKED_TOTAL).unwrap_or_default()
+}
+
+pub fn set_staked_total(staked_total: U256) {
+    set_key(STAKED_TOTAL, staked_total);
+}
+
+pub fn compounded_total() -> U256 {
+    get_key(COMPOUNDED_TOTAL).unwrap_or_default()
+}
+
+pub fn set_compounded_total(compounded_total: U256) {
+    set_key(COMPOUNDED_TOTAL, compounded_total);
+}
+
+/// Compounded rewards still staked, which are principal but earn no further reward.
+pub fn compounded_balance() -> U256 {
+    get_key(COMPOUNDED_BALANCE).unwrap_or_default()
+}
+
+pub fn set_compounded_balance(compounded_balance: U256) {
+    set_key(COMPOUNDED_BALANCE, compounded_balance);
+}
+
+pub fn staked_balance() -> U256 {
+    get_key(STAKED_BALANCE).unwrap_or_default()
+}
+
+pub fn set_staked_balance(staked_balance: U256) {
+    set_key(STAKED_BALANCE, staked_balance);
+}
+
+pub fn total_reward() -> U256 {
+    get_key(TOTAL_REWARD).unwrap_or_default()
+}
+
+pub fn set_total_reward(total_reward: U256) {
+    set_key(TOTAL_REWARD, total_reward);
+}
+
+pub fn reward_balance() -> U256 {
+    get_key(REWARD_BALANCE).unwrap_or_default()
+}
+
+pub fn set_reward_balance(reward_balance: U256) {
+    set_key(REWARD_BALANCE, reward_balance);
+}
+
+pub fn early_withdraw_reward() -> U256 {
+    get_key(EARLY_WITHDRAW_REWARD).unwrap_or_default()
+}
+
+pub fn set_early_withdraw_reward(early_withdraw_reward: U256) {
+    set_key(EARLY_WITHDRAW_REWARD, early_withdraw_reward);
+}
+
+pub fn finalized() -> bool {
+    get_key(FINALIZED).unwrap_or_default()
+}
+
+pub fn set_finalized(finalized: bool) {
+    set_key(FINALIZED, finalized);
+}
+
+pub fn owner() -> Key {
+    get_key(OWNER).unwrap_or_revert()
+}
+
+pub fn set_owner(owner: Key) {
+    set_key(OWNER, owner);
+}
+
+pub fn merkle_root() -> Option<[u8; 32]> {
+    get_key(MERKLE_ROOT).unwrap_or_default()
+}
+
+pub fn set_merkle_root(merkle_root: Option<[u8; 32]>) {
+    set_key(MERKLE_ROOT, merkle_root);
+}
+
+pub fn min_stake() -> U256 {
+    get_key(MIN_STAKE).unwrap_or_default()
+}
+
+pub fn set_min_stake(min_stake: U256) {
+    set_key(MIN_STAKE, min_stake);
+}
+
+pub fn max_stake_per_address() -> U256 {
+    get_key(MAX_STAKE_PER_ADDRESS).unwrap_or_default()
+}
+
+pub fn set_max_stake_per_address(max_stake_per_address: U256) {
+    set_key(MAX_STAKE_PER_ADDRESS, max_stake_per_address);
+}
+
+pub fn max_stakers() -> u64 {
+    get_key(MAX_STAKERS).unwrap_or_default()
+}
+
+pub fn set_max_stakers(max_stakers: u64) {
+    set_key(MAX_STAKERS, max_stakers);
+}
+
+pub fn total_claimed() -> U256 {
+    get_key(TOTAL_CLAIMED).unwrap_or_default()
+}
+
+pub fn set_total_claimed(total_claimed: U256) {
+    set_key(TOTAL_CLAIMED, total_claimed);
+}
+
+pub fn refund_pool() -> U256 {
+    get_key(REFUND_POOL).unwrap_or_default()
+}
+
+pub fn set_refund_pool(refund_pool: U256) {
+    set_key(REFUND_POOL, refund_pool);
+}
+
+pub fn refunded() -> U256 {
+    get_key(REFUNDED).unwrap_or_default()
+}
+
+pub fn set_refunded(refunded: U256) {
+    set_key(REFUNDED, refunded);
+}
+
+/// Funded amounts of the funders who already claimed their refund.
+pub fn refunded_funding() -> U256 {
+    get_key(REFUNDED_FUNDING).unwrap_or_default()
+}
+
+pub fn set_refunded_funding(refunded_funding: U256) {
+    set_key(REFUNDED_FUNDING, refunded_funding);
+}
+
+pub fn locked() -> bool {
+    get_key(LOCKED).unwrap_or_default()
+}
+
+pub fn set_locked(locked: bool) {
+    set_key(LOCKED, locked);
+}
+
+/// Returns which callers may hold a stake, set at install.
+pub fn caller_policy() -> CallerPolicy {
+    get_key(CALLER_POLICY).unwrap_or_revert()
+}
+
+pub fn set_caller_policy(caller_policy: CallerPolicy) {
+    set_key(CALLER_POLICY, caller_policy);
+}
+
+/// Returns the interface of the token set at install.
+pub fn asset_kind() -> AssetKind {
+    get_key(ASSET_KIND).unwrap_or_revert()
+}
+
+pub fn set_asset_kind(asset_kind: AssetKind) {
+    set_key(ASSET_KIND, asset_kind);
+}
+
+pub fn paused() -> bool {
+    get_key(PAUSED).unwrap_or_default()
+}
+
+pub fn set_paused(paused: bool) {
+    set_key(PAUSED, paused);
+}
+
+/// Returns `true` for pools installed with [`AssetKind::Native`].
+pub fn native() -> bool {
+    asset_kind() == AssetKind::Native
+}
+
+/// Returns the purse holding the pool's motes in native mode.
+pub fn pool_purse() -> URef {
+    detail::get_uref(POOL_PURSE)
+}
+
+pub fn set_pool_purse(purse: URef) {
+    runtime::put_key(POOL_PURSE, purse.into());
+}
+
+pub fn penalty_bps() -> u64 {
+    get_key(PENALTY_BPS).unwrap_or_default()
+}
+
+pub fn set_penalty_bps(penalty_bps: u64) {
+    set_key(PENALTY_BPS, penalty_bps);
+}
+
+pub fn penalty_decays() -> bool {
+    get_key(PENALTY_DECAYS).unwrap_or_default()
+}
+
+pub fn set_penalty_decays(penalty_decays: bool) {
+    set_key(PENALTY_DECAYS, penalty_decays);
+}
+
+pub fn penalty_treasury() -> Option<Key> {
+    get_key(PENALTY_TREASURY).unwrap_or_default()
+}
+
+pub fn set_penalty_treasury(penalty_treasury: Option<Key>) {
+    set_key(PENALTY_TREASURY, penalty_treasury);
+}
+
 pub fn contract_package_hash() -> ContractPackageHash {
     let call_stacks = get_call_stack();
     let last_entry = call_stacks.last().unwrap_or_revert();
@@ -146,15 +708,61 @@ pub fn emit(event: &CEP47Event) {
                 param.insert("stake_amount", amount.to_string());
                 events.push(param);
         }
-        CEP47Event::Withdraw { amount} => {
+        CEP47Event::Withdraw { amount, reward, penalty } => {
                 let mut param = BTreeMap::new();
                 param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
                 param.insert("event_type", "withdraw".to_string());
                 param.insert("staker", Key::from(detail::get_immediate_caller_address().ok().unwrap()).to_formatted_string());
                 param.insert("withdrawn_amount", amount.to_string());
+                param.insert("reward", reward.to_string());
+                param.insert("penalty", penalty.to_string());
                 events.push(param);
             
         }
+        CEP47Event::ClaimReward { amount } => {
+                let mut param = BTreeMap::new();
+                param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
+                param.insert("event_type", "claim_reward".to_string());
+                param.insert("staker", Key::from(detail::get_immediate_caller_address().ok().unwrap()).to_formatted_string());
+                param.insert("reward", amount.to_string());
+                events.push(param);
+        }
+        CEP47Event::Compound { amount } => {
+                let mut param = BTreeMap::new();
+                param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
+                param.insert("event_type", "compound".to_string());
+                param.insert("staker", Key::from(detail::get_immediate_caller_address().ok().unwrap()).to_formatted_string());
+                param.insert("compounded_amount", amount.to_string());
+                events.push(param);
+        }
+        CEP47Event::Finalize { returned_reward } => {
+                let mut param = BTreeMap::new();
+                param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
+                param.insert("event_type", "finalize".to_string());
+                param.insert("returned_reward", returned_reward.to_string());
+                events.push(param);
+        }
+        CEP47Event::ClaimRefund { funder, amount } => {
+                let mut param = BTreeMap::new();
+                param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
+                param.insert("event_type", "claim_refund".to_string());
+                param.insert("funder", funder.to_formatted_string());
+                param.insert("amount", amount.to_string());
+                events.push(param);
+        }
+        CEP47Event::RecoverToken {
+            token,
+            recipient,
+            amount
+        } => {
+                let mut param = BTreeMap::new();
+                param.insert(CONTRACT_PACKAGE_HASH, package.to_string());
+                param.insert("event_type", "recover_token".to_string());
+                param.insert("token", token.to_formatted_string());
+                param.insert("recipient", recipient.to_formatted_string());
+                param.insert("amount", amount.to_string());
+                events.push(param);
+        }
         CEP47Event::AddReward {
             reward_amount,
             withdrawable_amount
@@ -5,10 +5,14 @@ use casper_contract::{
     contract_api::{runtime, storage},
     unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_types::{bytesrepr::FromBytes, system::CallStackElement, ApiError, CLTyped, URef};
+use casper_types::{
+    bytesrepr::FromBytes, system::CallStackElement, ApiError, CLTyped, URef, U256, U512,
+};
 
 use crate::{Error};
 use crate::address::Address;
+use crate::caller_policy::CallerPolicy;
+use crate::data::{self, AllowedContracts};
 
 /// Gets [`URef`] under a name.
 pub(crate) fn get_uref(name: &str) -> URef {
@@ -63,6 +67,31 @@ pub(crate) fn get_immediate_caller_address() -> Result<Address, Error> {
         .ok_or(Error::InvalidContext)
 }
 
+/// Gets the immediate caller as the address credited with a stake.
+///
+/// Unlike [`get_immediate_caller_address`] this enforces the pool's [`CallerPolicy`]. Only `stake`
+/// and `compound` apply it, so a contract delisted after staking can still withdraw and claim.
+pub(crate) fn get_staker_address() -> Result<Address, Error> {
+    let call_stack_element = get_immediate_call_stack_item().ok_or(Error::InvalidContext)?;
+    if let CallStackElement::StoredContract {
+        contract_package_hash,
+        ..
+    } = &call_stack_element
+    {
+        let allowed = match data::caller_policy() {
+            CallerPolicy::AccountsOnly => false,
+            CallerPolicy::AllowlistedContracts => {
+                AllowedContracts::instance().contains(contract_package_hash)
+            }
+            CallerPolicy::Anyone => true,
+        };
+        if !allowed {
+            return Err(Error::CallerNotAllowed);
+        }
+    }
+    Ok(call_stack_element_to_address(call_stack_element))
+}
+
 /// Gets the caller address which is stored on the top of the call stack.
 ///
 /// This is similar to what [`runtime::get_caller`] does but it also supports stored contracts.
@@ -76,3 +105,15 @@ pub(crate) fn get_caller_address() -> Result<Address, Error> {
     let address = call_stack_element_to_address(top_of_the_stack);
     Ok(address)
 }
+
+/// Returns the current block time.
+pub(crate) fn block_time() -> u64 {
+    u64::from(runtime::get_blocktime())
+}
+
+/// Converts a token amount into motes.
+pub(crate) fn u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
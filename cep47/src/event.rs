@@ -1,5 +1,5 @@
 
-use casper_types::U256;
+use casper_types::{ContractHash, Key, U256};
 
 
 pub enum CEP47Event {
@@ -8,6 +8,26 @@ pub enum CEP47Event {
     },
     Withdraw {
         amount: U256,
+        reward: U256,
+        penalty: U256,
+    },
+    ClaimReward {
+        amount: U256,
+    },
+    Compound {
+        amount: U256,
+    },
+    Finalize {
+        returned_reward: U256,
+    },
+    ClaimRefund {
+        funder: Key,
+        amount: U256,
+    },
+    RecoverToken {
+        token: ContractHash,
+        recipient: Key,
+        amount: U256,
     },
     AddReward {
         reward_amount: U256,
@@ -7,9 +7,19 @@ pub mod data;
 pub mod event;
 pub mod detail;
 pub mod address;
+pub mod staker_info;
+pub mod phase;
+pub mod merkle;
+pub mod asset;
+pub mod caller_policy;
+pub use staking_rewards as rewards;
 
 
 pub use cep47::{Error, CEP20STK};
+pub use staker_info::StakerInfo;
+pub use phase::Phase;
+pub use asset::{AssetKind, StakingAsset};
+pub use caller_policy::CallerPolicy;
 pub use contract_utils;
 
 use alloc::{collections::BTreeMap, string::String};
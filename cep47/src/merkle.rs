@@ -0,0 +1,30 @@
+//! Merkle-proof verification for allowlisted pools.
+use alloc::vec::Vec;
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{bytesrepr::ToBytes, Key, U256};
+
+/// Returns the leaf committing to `staker` and its `allocation`, the blake2b hash of their
+/// serialized bytes.
+pub fn leaf(staker: &Key, allocation: U256) -> [u8; 32] {
+    let mut bytes: Vec<u8> = staker.to_bytes().unwrap_or_revert();
+    bytes.append(&mut allocation.to_bytes().unwrap_or_revert());
+    runtime::blake2b(bytes)
+}
+
+/// Returns `true` if `proof` links `leaf` to `root`.
+///
+/// Pairs are hashed in sorted order, so proofs don't need to carry left/right flags.
+pub fn verify(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (first, second) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(&first);
+        bytes.extend_from_slice(&second);
+        runtime::blake2b(bytes)
+    });
+    computed == root
+}
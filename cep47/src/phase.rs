@@ -0,0 +1,88 @@
+//! Implementation of [`Phase`], the stage of the pool's schedule.
+use alloc::vec::Vec;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped,
+};
+
+use crate::data;
+
+/// The stage of the pool, derived from the schedule set at install.
+#[repr(u8)]
+#[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Phase {
+    /// Before `staking_starts`.
+    NotStarted = 0,
+    /// Between `staking_starts` and `staking_ends`, deposits are accepted.
+    Staking = 1,
+    /// Between `staking_ends` and `withdraw_starts`, stakes are locked.
+    Locked = 2,
+    /// Between `withdraw_starts` and `withdraw_ends`, withdrawals earn the early reward.
+    EarlyWithdraw = 3,
+    /// After `withdraw_ends`, withdrawals earn a share of the remaining reward.
+    Closed = 4,
+    /// The pool was finalized. Stakes left in it can still be withdrawn with the reward reserved
+    /// for them, but nothing else changes.
+    Finalized = 5,
+}
+
+impl Phase {
+    /// Returns the phase of the pool at block time `now`.
+    pub fn at(now: u64) -> Phase {
+        if data::finalized() {
+            Phase::Finalized
+        } else if now < data::staking_starts() {
+            Phase::NotStarted
+        } else if now < data::staking_ends() {
+            Phase::Staking
+        } else if now < data::withdraw_starts() {
+            Phase::Locked
+        } else if now < data::withdraw_ends() {
+            Phase::EarlyWithdraw
+        } else {
+            Phase::Closed
+        }
+    }
+
+    /// Returns `true` if rewards can still be added in this phase.
+    pub fn accepts_rewards(&self) -> bool {
+        matches!(self, Phase::NotStarted | Phase::Staking | Phase::Locked)
+    }
+
+    /// Returns `true` if stakes can be withdrawn in this phase.
+    pub fn accepts_withdrawals(&self) -> bool {
+        matches!(self, Phase::EarlyWithdraw | Phase::Closed | Phase::Finalized)
+    }
+}
+
+impl CLTyped for Phase {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for Phase {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for Phase {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let phase = match tag {
+            0 => Phase::NotStarted,
+            1 => Phase::Staking,
+            2 => Phase::Locked,
+            3 => Phase::EarlyWithdraw,
+            4 => Phase::Closed,
+            5 => Phase::Finalized,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        Ok((phase, remainder))
+    }
+}
@@ -0,0 +1,70 @@
+//! Implementation of [`StakerInfo`], a consolidated view of a single staker's position.
+use alloc::vec::Vec;
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, U256,
+};
+
+/// Everything a frontend needs to know about one staker, in a single value.
+///
+/// A copy is stored in the `staker_info` dictionary whenever the staker's position changes, so
+/// the reward fields of the stored copy reflect the pool state at that moment.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct StakerInfo {
+    /// Amount currently staked.
+    pub staked: U256,
+    /// Reward paid on top of `staked` if the whole stake were withdrawn now.
+    pub pending_reward: U256,
+    /// Reward paid on top of `staked` if the whole stake were withdrawn after close.
+    pub projected_reward: U256,
+    /// Block time of the first deposit.
+    pub first_staked_at: u64,
+    /// Number of accepted deposits.
+    pub deposits: u32,
+}
+
+/// Serialised as `((staked, pending_reward, projected_reward), (first_staked_at, deposits))`, so
+/// clients can decode it with the standard tuple types.
+impl CLTyped for StakerInfo {
+    fn cl_type() -> CLType {
+        <((U256, U256, U256), (u64, u32))>::cl_type()
+    }
+}
+
+impl ToBytes for StakerInfo {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.staked.to_bytes()?);
+        result.append(&mut self.pending_reward.to_bytes()?);
+        result.append(&mut self.projected_reward.to_bytes()?);
+        result.append(&mut self.first_staked_at.to_bytes()?);
+        result.append(&mut self.deposits.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.staked.serialized_length()
+            + self.pending_reward.serialized_length()
+            + self.projected_reward.serialized_length()
+            + self.first_staked_at.serialized_length()
+            + self.deposits.serialized_length()
+    }
+}
+
+impl FromBytes for StakerInfo {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (staked, remainder) = U256::from_bytes(bytes)?;
+        let (pending_reward, remainder) = U256::from_bytes(remainder)?;
+        let (projected_reward, remainder) = U256::from_bytes(remainder)?;
+        let (first_staked_at, remainder) = u64::from_bytes(remainder)?;
+        let (deposits, remainder) = u32::from_bytes(remainder)?;
+        let staker_info = StakerInfo {
+            staked,
+            pending_reward,
+            projected_reward,
+            first_staked_at,
+            deposits,
+        };
+        Ok((staker_info, remainder))
+    }
+}
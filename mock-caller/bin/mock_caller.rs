@@ -0,0 +1,99 @@
+//! Stored code used by the staking tests to call a pool from something other than session code.
+//!
+//! `stake_as_session` is stored session code and runs in the calling account's context, while
+//! `stake_as_contract`, `withdraw_as_contract` and `compound_as_contract` run as the contract
+//! itself. Stakes approve `spender`, the pool's package, on `token` first.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec, vec::Vec};
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{
+    runtime_args, CLType, CLTyped, ContractHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, RuntimeArgs, U256,
+};
+
+fn stake() {
+    let pool = runtime::get_named_arg::<ContractHash>("pool");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let token = runtime::get_named_arg::<ContractHash>("token");
+    let spender = runtime::get_named_arg::<Key>("spender");
+    let _: () = runtime::call_contract(
+        token,
+        "approve",
+        runtime_args! { "spender" => spender, "amount" => amount },
+    );
+    let _: U256 = runtime::call_contract(
+        pool,
+        "stake",
+        runtime_args! {
+            "amount" => amount,
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+    );
+}
+
+#[no_mangle]
+fn stake_as_session() {
+    stake();
+}
+
+#[no_mangle]
+fn stake_as_contract() {
+    stake();
+}
+
+#[no_mangle]
+fn withdraw_as_contract() {
+    let pool = runtime::get_named_arg::<ContractHash>("pool");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let _: U256 = runtime::call_contract(pool, "withdraw", runtime_args! { "amount" => amount });
+}
+
+#[no_mangle]
+fn compound_as_contract() {
+    let pool = runtime::get_named_arg::<ContractHash>("pool");
+    let _: U256 = runtime::call_contract(
+        pool,
+        "compound",
+        runtime_args! {
+            "proof" => Vec::<[u8; 32]>::new(),
+            "allocation" => U256::zero()
+        },
+    );
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+
+    let mut entry_points = EntryPoints::new();
+    for (name, entry_point_type) in [
+        ("stake_as_session", EntryPointType::Session),
+        ("stake_as_contract", EntryPointType::Contract),
+        ("withdraw_as_contract", EntryPointType::Contract),
+        ("compound_as_contract", EntryPointType::Contract),
+    ] {
+        entry_points.add_entry_point(EntryPoint::new(
+            name,
+            vec![
+                Parameter::new("pool", ContractHash::cl_type()),
+                Parameter::new("amount", U256::cl_type()),
+                Parameter::new("token", ContractHash::cl_type()),
+                Parameter::new("spender", Key::cl_type()),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            entry_point_type,
+        ));
+    }
+
+    let (contract_hash, _) = storage::new_contract(entry_points, None, None, None);
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+}
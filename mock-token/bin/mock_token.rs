@@ -0,0 +1,308 @@
+//! Minimal token used by the staking tests to stand in for hostile token contracts.
+//!
+//! `transfer_from` reverts unless the owner approved the spender for the amount, as a real token
+//! does, so every test stakes and funds through an allowance. Optionally every transfer calls back
+//! into a pool entry point, as a malicious token would, or burns a fee out of the amount
+//! delivered, as deflationary tokens do.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes}, contracts::NamedKeys, runtime_args, ApiError, system::CallStackElement, CLType, CLTyped, CLValue,
+    ContractHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter,
+    RuntimeArgs, URef, U256,
+};
+
+const BALANCES: &str = "balances";
+const ALLOWANCES: &str = "allowances";
+const REENTRY_POOL: &str = "reentry_pool";
+const REENTRY_ENTRY_POINT: &str = "reentry_entry_point";
+const FEE_BPS: &str = "fee_bps";
+
+fn key_bytes(owner: &Key) -> [u8; 32] {
+    match owner {
+        Key::Account(account_hash) => account_hash.value(),
+        Key::Hash(hash) => *hash,
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Dictionary item key of a balance, the hex of the account or contract package hash.
+fn balance_key(owner: &Key) -> String {
+    to_hex(&key_bytes(owner))
+}
+
+/// Dictionary item key of an allowance, the hex of the hashed owner and spender.
+fn allowance_key(owner: &Key, spender: &Key) -> String {
+    let mut bytes = key_bytes(owner).to_vec();
+    bytes.extend_from_slice(&key_bytes(spender));
+    to_hex(&runtime::blake2b(bytes))
+}
+
+fn dictionary(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+}
+
+fn read<T: CLTyped + FromBytes>(name: &str) -> T {
+    let uref = runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    storage::read(uref).unwrap_or_revert().unwrap_or_revert()
+}
+
+fn write<T: CLTyped + ToBytes>(name: &str, value: T) {
+    let uref = runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    storage::write(uref, value);
+}
+
+fn balance(owner: &Key) -> U256 {
+    storage::dictionary_get(dictionary(BALANCES), &balance_key(owner))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn set_balance(owner: &Key, amount: U256) {
+    storage::dictionary_put(dictionary(BALANCES), &balance_key(owner), amount);
+}
+
+fn allowance_of(owner: &Key, spender: &Key) -> Option<U256> {
+    storage::dictionary_get(dictionary(ALLOWANCES), &allowance_key(owner, spender))
+        .unwrap_or_revert()
+}
+
+fn set_allowance(owner: &Key, spender: &Key, amount: U256) {
+    storage::dictionary_put(
+        dictionary(ALLOWANCES),
+        &allowance_key(owner, spender),
+        amount,
+    );
+}
+
+fn immediate_caller() -> Key {
+    let call_stack = runtime::get_call_stack();
+    match call_stack.into_iter().rev().nth(1).unwrap_or_revert() {
+        CallStackElement::Session { account_hash }
+        | CallStackElement::StoredSession { account_hash, .. } => Key::Account(account_hash),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        } => Key::Hash(contract_package_hash.value()),
+    }
+}
+
+fn move_balance(owner: Key, recipient: Key, amount: U256) {
+    let owner_balance = balance(&owner);
+    if owner_balance < amount {
+        runtime::revert(ApiError::InvalidArgument);
+    }
+    let fee = amount * read::<u64>(FEE_BPS) / 10_000u64;
+    set_balance(&owner, owner_balance - amount);
+    set_balance(&recipient, balance(&recipient) + amount - fee);
+
+    let pool: Option<ContractHash> = read(REENTRY_POOL);
+    if let Some(pool) = pool {
+        let entry_point: String = read(REENTRY_ENTRY_POINT);
+        let _: U256 = runtime::call_contract(
+            pool,
+            &entry_point,
+            runtime_args! {
+                "amount" => amount,
+                "proof" => Vec::<[u8; 32]>::new(),
+                "allocation" => U256::zero()
+            },
+        );
+    }
+}
+
+#[no_mangle]
+fn mint() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    set_balance(&owner, balance(&owner) + amount);
+}
+
+#[no_mangle]
+fn balance_of() {
+    let address = runtime::get_named_arg::<Key>("address");
+    runtime::ret(CLValue::from_t(balance(&address)).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer() {
+    let recipient = runtime::get_named_arg::<Key>("recipient");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    move_balance(immediate_caller(), recipient, amount);
+}
+
+#[no_mangle]
+fn approve() {
+    let spender = runtime::get_named_arg::<Key>("spender");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    set_allowance(&immediate_caller(), &spender, amount);
+}
+
+#[no_mangle]
+fn allowance() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    let spender = runtime::get_named_arg::<Key>("spender");
+    let ret = allowance_of(&owner, &spender).unwrap_or_default();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer_from() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    let recipient = runtime::get_named_arg::<Key>("recipient");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let spender = immediate_caller();
+    let allowance = allowance_of(&owner, &spender).unwrap_or_default();
+    if allowance < amount {
+        runtime::revert(ApiError::InvalidArgument);
+    }
+    set_allowance(&owner, &spender, allowance - amount);
+    move_balance(owner, recipient, amount);
+}
+
+#[no_mangle]
+fn set_reentry() {
+    let pool = runtime::get_named_arg::<Option<ContractHash>>("pool");
+    let entry_point = runtime::get_named_arg::<String>("entry_point");
+    write(REENTRY_POOL, pool);
+    write(REENTRY_ENTRY_POINT, entry_point);
+}
+
+#[no_mangle]
+fn set_fee() {
+    let fee_bps = runtime::get_named_arg::<u64>("fee_bps");
+    write(FEE_BPS, fee_bps);
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        String::from(BALANCES),
+        storage::new_dictionary(BALANCES).unwrap_or_revert().into(),
+    );
+    runtime::remove_key(BALANCES);
+    named_keys.insert(
+        String::from(ALLOWANCES),
+        storage::new_dictionary(ALLOWANCES).unwrap_or_revert().into(),
+    );
+    runtime::remove_key(ALLOWANCES);
+    named_keys.insert(
+        String::from(REENTRY_POOL),
+        storage::new_uref(Option::<ContractHash>::None).into(),
+    );
+    named_keys.insert(
+        String::from(REENTRY_ENTRY_POINT),
+        storage::new_uref(String::new()).into(),
+    );
+    named_keys.insert(String::from(FEE_BPS), storage::new_uref(0u64).into());
+
+    let (contract_hash, _) = storage::new_contract(get_entry_points(), Some(named_keys), None, None);
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "mint",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new("address", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "approve",
+        vec![
+            Parameter::new("spender", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "allowance",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("spender", Key::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_reentry",
+        vec![
+            Parameter::new("pool", Option::<ContractHash>::cl_type()),
+            Parameter::new("entry_point", String::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_fee",
+        vec![Parameter::new("fee_bps", u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
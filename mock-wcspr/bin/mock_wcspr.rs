@@ -0,0 +1,233 @@
+//! Minimal wrapped CSPR used by the staking tests to exercise the `WrappedCspr` asset kind.
+//!
+//! Balances are queried by `owner`, and transfers and approvals report failures by returning
+//! `Err` instead of reverting. Like the mock token, `transfer_from` needs an allowance from the
+//! owner.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, system::CallStackElement, ApiError, CLType, CLTyped, CLValue, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, URef, U256,
+};
+
+const BALANCES: &str = "balances";
+const ALLOWANCES: &str = "allowances";
+/// Error code returned when the owner's balance doesn't cover a transfer.
+const INSUFFICIENT_BALANCE: u32 = 1;
+/// Error code returned when the spender's allowance doesn't cover a transfer.
+const INSUFFICIENT_ALLOWANCE: u32 = 2;
+
+fn key_bytes(owner: &Key) -> [u8; 32] {
+    match owner {
+        Key::Account(account_hash) => account_hash.value(),
+        Key::Hash(hash) => *hash,
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Dictionary item key of a balance, the hex of the account or contract package hash.
+fn balance_key(owner: &Key) -> String {
+    to_hex(&key_bytes(owner))
+}
+
+/// Dictionary item key of an allowance, the hex of the hash of both keys, as in the mock token.
+fn allowance_key(owner: &Key, spender: &Key) -> String {
+    let mut bytes = key_bytes(owner).to_vec();
+    bytes.extend_from_slice(&key_bytes(spender));
+    to_hex(&runtime::blake2b(bytes))
+}
+
+fn dictionary(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+}
+
+fn balance(owner: &Key) -> U256 {
+    storage::dictionary_get(dictionary(BALANCES), &balance_key(owner))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn set_balance(owner: &Key, amount: U256) {
+    storage::dictionary_put(dictionary(BALANCES), &balance_key(owner), amount);
+}
+
+fn allowance_of(owner: &Key, spender: &Key) -> Option<U256> {
+    storage::dictionary_get(dictionary(ALLOWANCES), &allowance_key(owner, spender))
+        .unwrap_or_revert()
+}
+
+fn set_allowance(owner: &Key, spender: &Key, amount: U256) {
+    storage::dictionary_put(
+        dictionary(ALLOWANCES),
+        &allowance_key(owner, spender),
+        amount,
+    );
+}
+
+fn immediate_caller() -> Key {
+    let call_stack = runtime::get_call_stack();
+    match call_stack.into_iter().rev().nth(1).unwrap_or_revert() {
+        CallStackElement::Session { account_hash }
+        | CallStackElement::StoredSession { account_hash, .. } => Key::Account(account_hash),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        } => Key::Hash(contract_package_hash.value()),
+    }
+}
+
+fn move_balance(owner: Key, recipient: Key, amount: U256) -> Result<(), u32> {
+    let owner_balance = balance(&owner);
+    if owner_balance < amount {
+        return Err(INSUFFICIENT_BALANCE);
+    }
+    set_balance(&owner, owner_balance - amount);
+    set_balance(&recipient, balance(&recipient) + amount);
+    Ok(())
+}
+
+#[no_mangle]
+fn mint() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    set_balance(&owner, balance(&owner) + amount);
+}
+
+#[no_mangle]
+fn balance_of() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    runtime::ret(CLValue::from_t(balance(&owner)).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer() {
+    let recipient = runtime::get_named_arg::<Key>("recipient");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let ret = move_balance(immediate_caller(), recipient, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn approve() {
+    let spender = runtime::get_named_arg::<Key>("spender");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    set_allowance(&immediate_caller(), &spender, amount);
+    runtime::ret(CLValue::from_t(Result::<(), u32>::Ok(())).unwrap_or_revert());
+}
+
+fn spend_allowance(owner: &Key, amount: U256) -> Result<(), u32> {
+    let spender = immediate_caller();
+    let allowance = allowance_of(owner, &spender).unwrap_or_default();
+    if allowance < amount {
+        return Err(INSUFFICIENT_ALLOWANCE);
+    }
+    set_allowance(owner, &spender, allowance - amount);
+    Ok(())
+}
+
+#[no_mangle]
+fn transfer_from() {
+    let owner = runtime::get_named_arg::<Key>("owner");
+    let recipient = runtime::get_named_arg::<Key>("recipient");
+    let amount = runtime::get_named_arg::<U256>("amount");
+    let ret = if balance(&owner) < amount {
+        Err(INSUFFICIENT_BALANCE)
+    } else {
+        spend_allowance(&owner, amount).and_then(|()| move_balance(owner, recipient, amount))
+    };
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        String::from(BALANCES),
+        storage::new_dictionary(BALANCES).unwrap_or_revert().into(),
+    );
+    runtime::remove_key(BALANCES);
+    named_keys.insert(
+        String::from(ALLOWANCES),
+        storage::new_dictionary(ALLOWANCES)
+            .unwrap_or_revert()
+            .into(),
+    );
+    runtime::remove_key(ALLOWANCES);
+
+    let (contract_hash, _) =
+        storage::new_contract(get_entry_points(), Some(named_keys), None, None);
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "mint",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new("owner", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        Result::<(), u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "approve",
+        vec![
+            Parameter::new("spender", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        Result::<(), u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        Result::<(), u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
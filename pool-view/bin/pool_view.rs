@@ -0,0 +1,50 @@
+//! Session code used by the staking tests to read what a pool's view entry points return.
+//!
+//! A deploy calling a contract directly drops its return value, so this session calls
+//! `entry_point` on `pool` with the serialised `args` and stores the result under a named key of
+//! the calling account named after the entry point.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    ApiError, CLTyped, ContractHash, Key, RuntimeArgs, U256,
+};
+
+fn store<T: CLTyped + FromBytes + ToBytes>(
+    pool: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+) {
+    let ret: T = runtime::call_contract(pool, entry_point, args);
+    runtime::put_key(entry_point, storage::new_uref(ret).into());
+}
+
+#[no_mangle]
+fn call() {
+    let pool = runtime::get_named_arg::<ContractHash>("pool");
+    let entry_point = runtime::get_named_arg::<String>("entry_point");
+    let args: RuntimeArgs =
+        bytesrepr::deserialize(runtime::get_named_arg::<Vec<u8>>("args")).unwrap_or_revert();
+
+    match entry_point.as_str() {
+        "current_phase" => store::<u8>(pool, &entry_point, args),
+        "address" => store::<Option<ContractHash>>(pool, &entry_point, args),
+        "staker_count" => store::<u64>(pool, &entry_point, args),
+        "stakers" => store::<Vec<Key>>(pool, &entry_point, args),
+        "preview_stake" => store::<(U256, U256)>(pool, &entry_point, args),
+        "preview_withdraw" => store::<(U256, U256, U256)>(pool, &entry_point, args),
+        "staker_info" => store::<((U256, U256, U256), (u64, u32))>(pool, &entry_point, args),
+        "amount_staked" | "staked_at" | "total_staked_at" => {
+            store::<U256>(pool, &entry_point, args)
+        }
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
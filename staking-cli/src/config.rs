@@ -0,0 +1,441 @@
+//! The TOML pool configuration and its validation.
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use casper_types::{ContractHash, Key, U256, U512};
+use serde::Deserialize;
+use staking_client::{DeployParams, InstallArgs, Pool};
+
+/// A configuration that can't be turned into deploys, with a message meant for operators.
+#[derive(Debug, PartialEq)]
+pub struct ConfigError(pub String);
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn error<T>(message: String) -> Result<T, ConfigError> {
+    Err(ConfigError(message))
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub pool: PoolConfig,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub chain_name: String,
+    /// PEM secret key signing every deploy.
+    pub secret_key: PathBuf,
+    #[serde(default = "default_gas_price")]
+    pub gas_price: u64,
+    /// Deploy time to live, such as `30m` or `1h`.
+    #[serde(default = "default_ttl")]
+    pub ttl: String,
+    /// Motes paid for the install deploy.
+    #[serde(default = "default_install_payment")]
+    pub install_payment: u64,
+    /// Motes paid for any other deploy.
+    #[serde(default = "default_call_payment")]
+    pub call_payment: u64,
+}
+
+fn default_gas_price() -> u64 {
+    1
+}
+
+fn default_ttl() -> String {
+    "30m".to_string()
+}
+
+fn default_install_payment() -> u64 {
+    80_000_000_000
+}
+
+fn default_call_payment() -> u64 {
+    3_000_000_000
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Cep18,
+    WrappedCspr,
+    Erc20,
+    /// Native CSPR, implied when the pool has no `token`.
+    Native,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CallerPolicy {
+    AccountsOnly,
+    AllowlistedContracts,
+    Anyone,
+}
+
+impl Default for CallerPolicy {
+    fn default() -> Self {
+        CallerPolicy::AccountsOnly
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PoolConfig {
+    pub contract_name: String,
+    pub name: String,
+    /// `hash-...` of the staked token, omitted for pools staking native CSPR.
+    pub token: Option<String>,
+    /// Defaults to `cep18` with a `token` and to `native` without one.
+    pub asset_kind: Option<AssetKind>,
+    #[serde(default)]
+    pub caller_policy: CallerPolicy,
+    /// RFC 3339 dates, such as `2022-06-01T12:00:00Z`.
+    pub staking_starts: String,
+    pub staking_ends: String,
+    pub withdraw_starts: String,
+    pub withdraw_ends: String,
+    /// Token amounts are decimal strings, as they may not fit in a TOML integer.
+    pub staking_total: String,
+    #[serde(default = "default_min_stake")]
+    pub min_stake: String,
+    #[serde(default = "default_unlimited")]
+    pub max_stake_per_address: String,
+    #[serde(default)]
+    pub max_stakers: u64,
+    /// `hash-...` of the installed pool contract, needed by everything but `install`.
+    pub contract_hash: Option<String>,
+}
+
+fn default_min_stake() -> String {
+    "2".to_string()
+}
+
+fn default_unlimited() -> String {
+    "0".to_string()
+}
+
+/// The pool schedule, checked to be in order.
+#[derive(Debug, PartialEq)]
+pub struct Schedule {
+    pub staking_starts: SystemTime,
+    pub staking_ends: SystemTime,
+    pub withdraw_starts: SystemTime,
+    pub withdraw_ends: SystemTime,
+}
+
+/// Block time of `time`, in milliseconds since the Unix epoch.
+pub fn block_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+pub fn format_time(time: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(time).to_string()
+}
+
+fn parse_time(field: &str, value: &str) -> Result<SystemTime, ConfigError> {
+    humantime::parse_rfc3339_weak(value).or_else(|_| {
+        error(format!(
+            "{} `{}` is not a date, expected e.g. `2022-06-01T12:00:00Z`",
+            field, value
+        ))
+    })
+}
+
+pub fn parse_amount(field: &str, value: &str) -> Result<U256, ConfigError> {
+    U256::from_dec_str(value)
+        .or_else(|_| error(format!("{} `{}` is not a whole amount", field, value)))
+}
+
+pub fn parse_key(field: &str, value: &str) -> Result<Key, ConfigError> {
+    Key::from_formatted_str(value).or_else(|_| {
+        error(format!(
+            "{} `{}` is not an `account-hash-...` or `hash-...` key",
+            field, value
+        ))
+    })
+}
+
+fn parse_contract_hash(field: &str, value: &str) -> Result<ContractHash, ConfigError> {
+    match parse_key(field, value)? {
+        Key::Hash(hash) => Ok(ContractHash::new(hash)),
+        _ => error(format!("{} `{}` must be a `hash-...` key", field, value)),
+    }
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Config, ConfigError> {
+        toml::from_str(input).or_else(|error| self::error(format!("invalid configuration: {}", error)))
+    }
+
+    /// Parses the schedule, rejecting phases that end before they start.
+    pub fn schedule(&self) -> Result<Schedule, ConfigError> {
+        let pool = &self.pool;
+        let schedule = Schedule {
+            staking_starts: parse_time("staking_starts", &pool.staking_starts)?,
+            staking_ends: parse_time("staking_ends", &pool.staking_ends)?,
+            withdraw_starts: parse_time("withdraw_starts", &pool.withdraw_starts)?,
+            withdraw_ends: parse_time("withdraw_ends", &pool.withdraw_ends)?,
+        };
+        let order = [
+            ("staking_starts", schedule.staking_starts),
+            ("staking_ends", schedule.staking_ends),
+            ("withdraw_starts", schedule.withdraw_starts),
+            ("withdraw_ends", schedule.withdraw_ends),
+        ];
+        for pair in order.windows(2) {
+            let (earlier, earlier_time) = pair[0];
+            let (later, later_time) = pair[1];
+            // Withdrawals may open the moment staking closes, every other phase must last.
+            let ordered = if later == "withdraw_starts" {
+                later_time >= earlier_time
+            } else {
+                later_time > earlier_time
+            };
+            if !ordered {
+                return error(format!(
+                    "{} ({}) must be after {} ({})",
+                    later,
+                    format_time(later_time),
+                    earlier,
+                    format_time(earlier_time)
+                ));
+            }
+        }
+        Ok(schedule)
+    }
+
+    pub fn install_args(&self) -> Result<InstallArgs, ConfigError> {
+        let pool = &self.pool;
+        let schedule = self.schedule()?;
+        let staking_total = parse_amount("staking_total", &pool.staking_total)?;
+        if staking_total.is_zero() {
+            return error("staking_total must be positive".to_string());
+        }
+        let min_stake = parse_amount("min_stake", &pool.min_stake)?;
+        let max_stake_per_address =
+            parse_amount("max_stake_per_address", &pool.max_stake_per_address)?;
+        if !max_stake_per_address.is_zero() && max_stake_per_address < min_stake {
+            return error(format!(
+                "max_stake_per_address ({}) is below min_stake ({})",
+                max_stake_per_address, min_stake
+            ));
+        }
+        let (address, asset_kind) = match (&pool.token, pool.asset_kind) {
+            (Some(_), Some(AssetKind::Native)) => {
+                return error("native pools take no token".to_string());
+            }
+            (Some(token), asset_kind) => {
+                parse_contract_hash("token", token)?;
+                (token.clone(), asset_kind.unwrap_or(AssetKind::Cep18))
+            }
+            (None, None) | (None, Some(AssetKind::Native)) => (String::new(), AssetKind::Native),
+            (None, Some(asset_kind)) => {
+                return error(format!("asset_kind {:?} needs a token", asset_kind));
+            }
+        };
+        Ok(InstallArgs {
+            contract_name: pool.contract_name.clone(),
+            name: pool.name.clone(),
+            address,
+            staking_starts: block_time(schedule.staking_starts),
+            staking_ends: block_time(schedule.staking_ends),
+            withdraw_starts: block_time(schedule.withdraw_starts),
+            withdraw_ends: block_time(schedule.withdraw_ends),
+            staking_total,
+            min_stake,
+            max_stake_per_address,
+            max_stakers: pool.max_stakers,
+            asset_kind: asset_kind as u8,
+            caller_policy: pool.caller_policy as u8,
+        })
+    }
+
+    /// The installed pool, for every action but `install`.
+    pub fn pool(&self) -> Result<Pool, ConfigError> {
+        match &self.pool.contract_hash {
+            Some(contract_hash) => Ok(Pool::new(parse_contract_hash(
+                "contract_hash",
+                contract_hash,
+            )?)),
+            None => error("pool.contract_hash is required once the pool is installed".to_string()),
+        }
+    }
+
+    pub fn deploy_params(&self, install: bool) -> Result<DeployParams, ConfigError> {
+        let network = &self.network;
+        let ttl = humantime::parse_duration(&network.ttl)
+            .or_else(|_| error(format!("ttl `{}` is not a duration", network.ttl)))?;
+        let payment = if install {
+            network.install_payment
+        } else {
+            network.call_payment
+        };
+        Ok(DeployParams {
+            chain_name: network.chain_name.clone(),
+            timestamp_millis: block_time(SystemTime::now()),
+            ttl_millis: ttl.as_millis() as u64,
+            gas_price: network.gas_price,
+            payment_amount: U512::from(payment),
+        })
+    }
+}
+
+/// Renders the schedule as the dates each phase starts, with its length.
+pub fn timeline(schedule: &Schedule) -> String {
+    let length = |from: SystemTime, to: SystemTime| {
+        let duration = to.duration_since(from).unwrap_or_default();
+        humantime::format_duration(Duration::from_secs(duration.as_secs())).to_string()
+    };
+    let rows = [
+        (
+            "Staking opens",
+            schedule.staking_starts,
+            format!("staking for {}", length(schedule.staking_starts, schedule.staking_ends)),
+        ),
+        (
+            "Staking closes",
+            schedule.staking_ends,
+            format!("locked for {}", length(schedule.staking_ends, schedule.withdraw_starts)),
+        ),
+        (
+            "Withdrawals open",
+            schedule.withdraw_starts,
+            format!(
+                "early withdrawals for {}",
+                length(schedule.withdraw_starts, schedule.withdraw_ends)
+            ),
+        ),
+        (
+            "Pool closes",
+            schedule.withdraw_ends,
+            "full rewards from here on".to_string(),
+        ),
+    ];
+    rows.iter()
+        .map(|(label, time, note)| format!("{:<17} {}  {}\n", label, format_time(*time), note))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(withdraw_starts: &str, withdraw_ends: &str) -> Config {
+        Config::parse(&format!(
+            r#"
+            [network]
+            chain_name = "casper-test"
+            secret_key = "keys/secret_key.pem"
+
+            [pool]
+            contract_name = "ferrumx_staking"
+            name = "FerrumX"
+            token = "hash-7e3f01576650a939a96c2caa6dcc19df8d2ef1882e4b6603a375234e22e07e4f"
+            staking_starts = "2022-06-01T00:00:00Z"
+            staking_ends = "2022-06-08T00:00:00Z"
+            withdraw_starts = "{}"
+            withdraw_ends = "{}"
+            staking_total = "500000"
+            "#,
+            withdraw_starts, withdraw_ends
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_install_args_use_millisecond_block_time() {
+        let install_args = config("2022-06-15T00:00:00Z", "2022-07-01T00:00:00Z")
+            .install_args()
+            .unwrap();
+        assert_eq!(install_args.staking_starts, 1_654_041_600_000);
+        assert_eq!(install_args.withdraw_ends, 1_656_633_600_000);
+        assert_eq!(install_args.staking_total, U256::from(500_000));
+        assert_eq!(install_args.min_stake, U256::from(2));
+        assert_eq!(install_args.asset_kind, AssetKind::Cep18 as u8);
+    }
+
+    #[test]
+    fn test_token_kind_without_token_is_rejected() {
+        let config = Config::parse(
+            r#"
+            [network]
+            chain_name = "casper-test"
+            secret_key = "keys/secret_key.pem"
+
+            [pool]
+            contract_name = "ferrumx_staking"
+            name = "FerrumX"
+            asset_kind = "wrapped_cspr"
+            staking_starts = "2022-06-01T00:00:00Z"
+            staking_ends = "2022-06-08T00:00:00Z"
+            withdraw_starts = "2022-06-15T00:00:00Z"
+            withdraw_ends = "2022-07-01T00:00:00Z"
+            staking_total = "500000"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.install_args().unwrap_err(),
+            ConfigError("asset_kind WrappedCspr needs a token".to_string())
+        );
+    }
+
+    #[test]
+    fn test_withdraw_ends_before_withdraw_starts_is_rejected() {
+        let error = config("2022-07-01T00:00:00Z", "2022-06-15T00:00:00Z")
+            .schedule()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ConfigError(
+                "withdraw_ends (2022-06-15T00:00:00Z) must be after withdraw_starts \
+                 (2022-07-01T00:00:00Z)"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_withdrawals_may_open_when_staking_closes() {
+        assert!(config("2022-06-08T00:00:00Z", "2022-07-01T00:00:00Z")
+            .schedule()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_malformed_date_is_rejected() {
+        let error = config("next week", "2022-07-01T00:00:00Z")
+            .schedule()
+            .unwrap_err();
+        assert!(error.0.starts_with("withdraw_starts `next week` is not a date"));
+    }
+
+    #[test]
+    fn test_timeline_lists_phase_lengths() {
+        let schedule = config("2022-06-15T00:00:00Z", "2022-07-01T00:00:00Z")
+            .schedule()
+            .unwrap();
+        assert_eq!(
+            timeline(&schedule),
+            "Staking opens     2022-06-01T00:00:00Z  staking for 7days\n\
+             Staking closes    2022-06-08T00:00:00Z  locked for 7days\n\
+             Withdrawals open  2022-06-15T00:00:00Z  early withdrawals for 16days\n\
+             Pool closes       2022-07-01T00:00:00Z  full rewards from here on\n"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Emits deploy JSON installing and administering a staking pool described by a TOML file.
+//!
+//! ```text
+//! staking-cli --config pool.toml --dry-run install --wasm cep47-token.wasm
+//! staking-cli --config pool.toml --output install.json install --wasm cep47-token.wasm
+//! casper-client send-deploy --node-address http://localhost:7777 --input install.json
+//! ```
+mod config;
+
+use std::{error::Error, fs};
+
+use casper_types::{URef, U256};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use staking_client::{load_secret_key, DeployBuilder, ExecutableDeployItem};
+
+use crate::config::{parse_amount, parse_key, timeline, Config, ConfigError};
+
+fn app() -> App<'static, 'static> {
+    App::new("staking-cli")
+        .about("Builds deploys installing and administering a staking pool")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .value_name("FILE")
+                .required(true)
+                .help("TOML pool configuration"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .value_name("FILE")
+                .help("Writes the deploy JSON to FILE instead of stdout"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help("Validates the configuration and prints the timeline and action only"),
+        )
+        .subcommand(
+            SubCommand::with_name("install")
+                .about("Installs the pool")
+                .arg(
+                    Arg::with_name("wasm")
+                        .long("wasm")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Compiled cep47-token.wasm"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fund")
+                .about("Adds rewards to the pool")
+                .arg(amount_arg("reward", true))
+                .arg(
+                    amount_arg("withdrawable", true)
+                        .help("Part of the reward paid to early withdrawers, 0 for none"),
+                )
+                .arg(
+                    Arg::with_name("purse")
+                        .long("purse")
+                        .value_name("UREF")
+                        .help("Purse paying the motes, native pools only"),
+                ),
+        )
+        .subcommand(SubCommand::with_name("pause").about("Pauses deposits, claims and funding"))
+        .subcommand(SubCommand::with_name("unpause").about("Resumes a paused pool"))
+        .subcommand(
+            SubCommand::with_name("set-stake-limits")
+                .about("Replaces the stake limits")
+                .arg(amount_arg("min", true))
+                .arg(amount_arg("max-per-address", true))
+                .arg(
+                    Arg::with_name("max-stakers")
+                        .long("max-stakers")
+                        .value_name("COUNT")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-penalty")
+                .about("Configures the early exit penalty, before staking starts")
+                .arg(
+                    Arg::with_name("bps")
+                        .long("bps")
+                        .value_name("BPS")
+                        .help("Penalty in basis points, at most 2000")
+                        .required(true),
+                )
+                .arg(Arg::with_name("decays").long("decays"))
+                .arg(
+                    Arg::with_name("treasury")
+                        .long("treasury")
+                        .value_name("KEY")
+                        .help("Receives penalties, remaining stakers do when omitted"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-merkle-root")
+                .about("Gates staking behind an allowlist, or opens it when ROOT is omitted")
+                .arg(Arg::with_name("root").long("root").value_name("ROOT")),
+        )
+        .subcommand(
+            SubCommand::with_name("finalize").about("Sets unused rewards aside for funders"),
+        )
+        .subcommand(
+            SubCommand::with_name("claim-refund")
+                .about("Pays a funder its share of unused rewards"),
+        )
+}
+
+fn amount_arg(name: &'static str, required: bool) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .value_name("AMOUNT")
+        .required(required)
+}
+
+fn invalid<T>(message: String) -> Result<T, Box<dyn Error>> {
+    Err(Box::new(ConfigError(message)))
+}
+
+fn number(matches: &ArgMatches, name: &str) -> Result<u64, Box<dyn Error>> {
+    let value = matches.value_of(name).unwrap_or_default();
+    match value.parse() {
+        Ok(number) => Ok(number),
+        Err(_) => invalid(format!("--{} `{}` is not a number", name, value)),
+    }
+}
+
+fn amount(matches: &ArgMatches, name: &str) -> Result<U256, Box<dyn Error>> {
+    match matches.value_of(name) {
+        Some(value) => Ok(parse_amount(&format!("--{}", name), value)?),
+        None => Ok(U256::zero()),
+    }
+}
+
+fn merkle_root(matches: &ArgMatches) -> Result<Option<[u8; 32]>, Box<dyn Error>> {
+    let root = match matches.value_of("root") {
+        Some(root) => root,
+        None => return Ok(None),
+    };
+    let mut bytes = [0u8; 32];
+    match hex::decode_to_slice(root, &mut bytes) {
+        Ok(()) => Ok(Some(bytes)),
+        Err(_) => invalid(format!("--root `{}` is not 32 hex encoded bytes", root)),
+    }
+}
+
+/// Builds the session of the chosen action, and whether it is the install.
+fn session(
+    config: &Config,
+    matches: &ArgMatches,
+) -> Result<(ExecutableDeployItem, bool), Box<dyn Error>> {
+    let (name, action) = matches.subcommand();
+    let action = action.expect("a subcommand is required");
+    if name == "install" {
+        let install_args = config.install_args()?;
+        let wasm = fs::read(action.value_of("wasm").unwrap_or_default())?;
+        return Ok((install_args.session(wasm), true));
+    }
+    let pool = config.pool()?;
+    let session = match name {
+        "fund" => {
+            let purse = match action.value_of("purse") {
+                Some(purse) => match URef::from_formatted_str(purse) {
+                    Ok(purse) => Some(purse),
+                    Err(_) => return invalid(format!("--purse `{}` is not a URef", purse)),
+                },
+                None => None,
+            };
+            if config.pool.token.is_none() && purse.is_none() {
+                return invalid("native pools are funded from a --purse".to_string());
+            }
+            let reward = amount(action, "reward")?;
+            let withdrawable = amount(action, "withdrawable")?;
+            if withdrawable > reward {
+                return invalid(format!(
+                    "--withdrawable ({}) exceeds --reward ({})",
+                    withdrawable, reward
+                ));
+            }
+            pool.add_reward(reward, withdrawable, purse)
+        }
+        "pause" => pool.set_paused(true),
+        "unpause" => pool.set_paused(false),
+        "set-stake-limits" => pool.set_stake_limits(
+            amount(action, "min")?,
+            amount(action, "max-per-address")?,
+            number(action, "max-stakers")?,
+        ),
+        "set-penalty" => {
+            let treasury = match action.value_of("treasury") {
+                Some(treasury) => Some(parse_key("--treasury", treasury)?),
+                None => None,
+            };
+            pool.set_penalty(number(action, "bps")?, action.is_present("decays"), treasury)
+        }
+        "set-merkle-root" => pool.set_merkle_root(merkle_root(action)?),
+        "finalize" => pool.finalize(),
+        "claim-refund" => pool.claim_refund(),
+        _ => unreachable!("unknown subcommand {}", name),
+    };
+    Ok((session, false))
+}
+
+fn describe(session: &ExecutableDeployItem) -> String {
+    match session {
+        ExecutableDeployItem::ModuleBytes { module_bytes, args } => format!(
+            "install {} bytes of wasm with {} args",
+            module_bytes.len(),
+            args.len()
+        ),
+        ExecutableDeployItem::StoredContractByHash {
+            hash,
+            entry_point,
+            args,
+        } => {
+            let args: Vec<String> = args
+                .named_args()
+                .map(|arg| format!("{}: {:?}", arg.name(), arg.cl_value().cl_type()))
+                .collect();
+            format!(
+                "call {} on hash-{} ({})",
+                entry_point,
+                hex::encode(hash.value()),
+                args.join(", ")
+            )
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let matches = app().get_matches();
+    let config = Config::parse(&fs::read_to_string(
+        matches.value_of("config").unwrap_or_default(),
+    )?)?;
+    let schedule = config.schedule()?;
+    let (session, install) = session(&config, &matches)?;
+
+    if matches.is_present("dry-run") {
+        print!("{}", timeline(&schedule));
+        println!("\n{}", describe(&session));
+        return Ok(());
+    }
+
+    let secret_key = load_secret_key(&config.network.secret_key)?;
+    let params = config.deploy_params(install)?;
+    let mut deploy = DeployBuilder::new(params, (&secret_key).into(), session).build()?;
+    deploy.sign(&secret_key)?;
+    let json = serde_json::to_string_pretty(&deploy.to_json()?)?;
+    match matches.value_of("output") {
+        Some(output) => fs::write(output, json)?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,215 @@
+//! Offline construction, hashing and signing of deploys.
+use std::{
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    crypto, runtime_args, ContractHash, PublicKey, RuntimeArgs, SecretKey, U512,
+};
+use serde_json::{json, Value};
+
+use crate::Error;
+
+/// Loads a secret key from a PEM file, as written by `casper-client keygen`.
+pub fn load_secret_key<P: AsRef<Path>>(path: P) -> Result<SecretKey, Error> {
+    SecretKey::from_file(path).map_err(|error| Error::SecretKey(error.to_string()))
+}
+
+fn blake2b_256(bytes: &[u8]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    let mut hasher = VarBlake2b::new(32).expect("32 is a valid blake2b output size");
+    hasher.update(bytes);
+    hasher.finalize_variable(|hash| result.copy_from_slice(hash));
+    result
+}
+
+/// The code a deploy runs, either for payment or as its session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExecutableDeployItem {
+    /// Wasm sent along with the deploy.
+    ModuleBytes { module_bytes: Vec<u8>, args: RuntimeArgs },
+    /// An entry point of a contract already on chain.
+    StoredContractByHash {
+        hash: ContractHash,
+        entry_point: String,
+        args: RuntimeArgs,
+    },
+}
+
+impl ExecutableDeployItem {
+    /// Standard payment paying `amount` motes from the account's main purse.
+    pub fn standard_payment(amount: U512) -> Self {
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Vec::new(),
+            args: runtime_args! { "amount" => amount },
+        }
+    }
+
+    /// Serializes `self` the way the node does when computing the body hash.
+    fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut result = Vec::new();
+        match self {
+            ExecutableDeployItem::ModuleBytes { module_bytes, args } => {
+                result.push(0u8);
+                result.append(&mut Bytes::from(module_bytes.clone()).to_bytes()?);
+                result.append(&mut args.to_bytes()?);
+            }
+            ExecutableDeployItem::StoredContractByHash {
+                hash,
+                entry_point,
+                args,
+            } => {
+                result.push(1u8);
+                result.append(&mut hash.value().to_bytes()?);
+                result.append(&mut entry_point.to_bytes()?);
+                result.append(&mut args.to_bytes()?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            ExecutableDeployItem::ModuleBytes { module_bytes, args } => json!({
+                "ModuleBytes": {
+                    "module_bytes": hex::encode(module_bytes),
+                    "args": args,
+                }
+            }),
+            ExecutableDeployItem::StoredContractByHash {
+                hash,
+                entry_point,
+                args,
+            } => json!({
+                "StoredContractByHash": {
+                    "hash": hex::encode(hash.value()),
+                    "entry_point": entry_point,
+                    "args": args,
+                }
+            }),
+        }
+    }
+}
+
+/// Header fields shared by every deploy an application sends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeployParams {
+    pub chain_name: String,
+    /// Creation time in milliseconds since the Unix epoch.
+    pub timestamp_millis: u64,
+    pub ttl_millis: u64,
+    pub gas_price: u64,
+    /// Motes paid through the standard payment.
+    pub payment_amount: U512,
+}
+
+/// Assembles a [`Deploy`] sent by `account`.
+pub struct DeployBuilder {
+    params: DeployParams,
+    account: PublicKey,
+    session: ExecutableDeployItem,
+}
+
+impl DeployBuilder {
+    pub fn new(params: DeployParams, account: PublicKey, session: ExecutableDeployItem) -> Self {
+        DeployBuilder {
+            params,
+            account,
+            session,
+        }
+    }
+
+    /// Builds the unsigned deploy, computing its body hash and deploy hash.
+    pub fn build(self) -> Result<Deploy, Error> {
+        let payment = ExecutableDeployItem::standard_payment(self.params.payment_amount);
+        let mut body = payment.to_bytes()?;
+        body.append(&mut self.session.to_bytes()?);
+        let body_hash = blake2b_256(&body);
+
+        let mut header = self.account.to_bytes()?;
+        header.append(&mut self.params.timestamp_millis.to_bytes()?);
+        header.append(&mut self.params.ttl_millis.to_bytes()?);
+        header.append(&mut self.params.gas_price.to_bytes()?);
+        header.append(&mut body_hash.to_bytes()?);
+        header.append(&mut Vec::<[u8; 32]>::new().to_bytes()?);
+        header.append(&mut self.params.chain_name.to_bytes()?);
+        let hash = blake2b_256(&header);
+
+        Ok(Deploy {
+            hash,
+            body_hash,
+            params: self.params,
+            account: self.account,
+            payment,
+            session: self.session,
+            approvals: Vec::new(),
+        })
+    }
+}
+
+/// A deploy ready to be sent, with the signatures collected so far.
+#[derive(Clone, Debug)]
+pub struct Deploy {
+    hash: [u8; 32],
+    body_hash: [u8; 32],
+    params: DeployParams,
+    account: PublicKey,
+    payment: ExecutableDeployItem,
+    session: ExecutableDeployItem,
+    approvals: Vec<(PublicKey, Vec<u8>)>,
+}
+
+impl Deploy {
+    pub fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    pub fn session(&self) -> &ExecutableDeployItem {
+        &self.session
+    }
+
+    /// Adds an approval of the deploy hash signed by `secret_key`.
+    pub fn sign(&mut self, secret_key: &SecretKey) -> Result<(), Error> {
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(self.hash, secret_key, &public_key);
+        self.approvals.push((public_key, signature.to_bytes()?));
+        Ok(())
+    }
+
+    /// Renders the deploy in the node's JSON format.
+    pub fn to_json(&self) -> Result<Value, Error> {
+        let timestamp = UNIX_EPOCH + Duration::from_millis(self.params.timestamp_millis);
+        let ttl = Duration::from_millis(self.params.ttl_millis);
+        let approvals = self
+            .approvals
+            .iter()
+            .map(|(signer, signature)| {
+                Ok(json!({
+                    "signer": hex::encode(signer.to_bytes()?),
+                    "signature": hex::encode(signature),
+                }))
+            })
+            .collect::<Result<Vec<Value>, Error>>()?;
+        Ok(json!({
+            "hash": hex::encode(self.hash),
+            "header": {
+                "account": hex::encode(self.account.to_bytes()?),
+                "timestamp": humantime::format_rfc3339_millis(timestamp).to_string(),
+                "ttl": humantime::format_duration(ttl).to_string(),
+                "gas_price": self.params.gas_price,
+                "body_hash": hex::encode(self.body_hash),
+                "dependencies": [],
+                "chain_name": self.params.chain_name,
+            },
+            "payment": self.payment.to_json(),
+            "session": self.session.to_json(),
+            "approvals": approvals,
+        }))
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::{self, Display, Formatter};
+
+use casper_types::{bytesrepr, CLValueError};
+
+/// Errors raised while building a deploy.
+#[derive(Debug)]
+pub enum Error {
+    /// The secret key file could not be read or parsed.
+    SecretKey(String),
+    /// A session argument could not be converted into a `CLValue`.
+    Argument(CLValueError),
+    /// Part of the deploy could not be serialized for hashing.
+    Serialization(bytesrepr::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::SecretKey(error) => write!(f, "invalid secret key: {}", error),
+            Error::Argument(error) => write!(f, "invalid session argument: {:?}", error),
+            Error::Serialization(error) => write!(f, "serialization failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<CLValueError> for Error {
+    fn from(error: CLValueError) -> Self {
+        Error::Argument(error)
+    }
+}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::Serialization(error)
+    }
+}
@@ -0,0 +1,144 @@
+//! Builds deploys for the staking pool offline, as JSON accepted by `casper-client send-deploy`
+//! and the node's `account_put_deploy` RPC.
+//!
+//! [`Pool`] turns each entry point into a typed session, [`InstallArgs`] does the same for the
+//! install wasm, and [`DeployBuilder`] wraps a session into a [`Deploy`] that can be signed with a
+//! key loaded by [`load_secret_key`]. [`rewards`] holds the reward and penalty formulas the pool
+//! itself runs, to project payouts without a node.
+mod deploy;
+mod error;
+mod pool;
+
+pub use deploy::{load_secret_key, Deploy, DeployBuilder, DeployParams, ExecutableDeployItem};
+pub use error::Error;
+pub use pool::{approve_and_stake, InstallArgs, Pool};
+pub use staking_rewards as rewards;
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::ExecutableDeployItem as NodeDeployItem;
+    use casper_node::types::{Deploy as NodeDeploy, TimeDiff, Timestamp};
+    use casper_types::{
+        bytesrepr::Bytes, runtime_args, ContractHash, PublicKey, RuntimeArgs, SecretKey, U256, U512,
+    };
+    use serde_json::Value;
+
+    use super::*;
+
+    const CHAIN_NAME: &str = "casper-test";
+    const TIMESTAMP_MILLIS: u64 = 1_654_000_000_000;
+
+    fn secret_key() -> SecretKey {
+        SecretKey::ed25519_from_bytes([1u8; 32]).unwrap()
+    }
+
+    fn params(payment_amount: u64) -> DeployParams {
+        DeployParams {
+            chain_name: CHAIN_NAME.to_string(),
+            timestamp_millis: TIMESTAMP_MILLIS,
+            ttl_millis: 30 * 60 * 1_000,
+            gas_price: 1,
+            payment_amount: U512::from(payment_amount),
+        }
+    }
+
+    fn pool() -> Pool {
+        Pool::new(ContractHash::new([0x11; 32]))
+    }
+
+    fn golden(fixture: &str) -> Value {
+        serde_json::from_str(fixture).unwrap()
+    }
+
+    fn build(params: DeployParams, session: ExecutableDeployItem) -> Deploy {
+        let account = PublicKey::from(&secret_key());
+        DeployBuilder::new(params, account, session).build().unwrap()
+    }
+
+    #[test]
+    fn test_install_deploy_matches_golden() {
+        let install_args = InstallArgs {
+            contract_name: "ferrumx_staking".to_string(),
+            name: "FerrumX".to_string(),
+            address: "hash-7e3f01576650a939a96c2caa6dcc19df8d2ef1882e4b6603a375234e22e07e4f"
+                .to_string(),
+            staking_starts: 1_653_993_649,
+            staking_ends: 1_653_994_249,
+            withdraw_starts: 1_653_994_549,
+            withdraw_ends: 1_653_995_149,
+            staking_total: U256::from(500_000),
+            min_stake: U256::from(2),
+            max_stake_per_address: U256::zero(),
+            max_stakers: 0,
+            asset_kind: 0,
+            caller_policy: 0,
+        };
+        let wasm = b"\0asm\x01\0\0\0".to_vec();
+        let deploy = build(params(80_000_000_000), install_args.session(wasm));
+        assert_eq!(
+            deploy.to_json().unwrap(),
+            golden(include_str!("../fixtures/install_unsigned.json"))
+        );
+    }
+
+    #[test]
+    fn test_signed_stake_deploy_matches_golden() {
+        let session = pool().stake(U256::from(1_000), Vec::new(), U256::zero(), None);
+        let mut deploy = build(params(3_000_000_000), session);
+        deploy.sign(&secret_key()).unwrap();
+        assert_eq!(
+            deploy.to_json().unwrap(),
+            golden(include_str!("../fixtures/stake_signed.json"))
+        );
+    }
+
+    #[test]
+    fn test_withdraw_deploy_matches_golden() {
+        let params = DeployParams {
+            timestamp_millis: TIMESTAMP_MILLIS + 60_123,
+            ttl_millis: 60 * 60 * 1_000,
+            ..params(3_000_000_000)
+        };
+        let deploy = build(params, pool().withdraw(U256::from(1_000)));
+        assert_eq!(
+            deploy.to_json().unwrap(),
+            golden(include_str!("../fixtures/withdraw_unsigned.json"))
+        );
+    }
+
+    #[test]
+    fn test_signing_keeps_deploy_hash() {
+        let session = pool().add_reward(U256::from(5_000), U256::from(1_000), None);
+        let mut deploy = build(params(3_000_000_000), session);
+        let hash = deploy.hash();
+        deploy.sign(&secret_key()).unwrap();
+        assert_eq!(deploy.hash(), hash);
+        assert_eq!(deploy.to_json().unwrap()["approvals"].as_array().unwrap().len(), 1);
+    }
+
+    /// The golden fixtures come from this crate, so the hash is also checked against the node's
+    /// own `Deploy`, which is what `account_put_deploy` verifies approvals against.
+    #[test]
+    fn test_deploy_hash_matches_casper_node() {
+        let deploy = build(params(3_000_000_000), pool().withdraw(U256::from(1_000)));
+        let node_deploy = NodeDeploy::new(
+            Timestamp::from(TIMESTAMP_MILLIS),
+            TimeDiff::from(30 * 60 * 1_000),
+            1,
+            Vec::new(),
+            CHAIN_NAME.to_string(),
+            NodeDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: runtime_args! { "amount" => U512::from(3_000_000_000u64) },
+            },
+            NodeDeployItem::StoredContractByHash {
+                hash: pool().contract_hash(),
+                entry_point: "withdraw".to_string(),
+                args: runtime_args! { "amount" => U256::from(1_000) },
+            },
+            &secret_key(),
+            None,
+        );
+        assert_eq!(deploy.hash(), node_deploy.id().inner().value());
+    }
+}
@@ -0,0 +1,287 @@
+//! Typed sessions for installing the staking pool and calling its entry points.
+use casper_types::{runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256};
+
+use crate::ExecutableDeployItem;
+
+/// Arguments of the pool's install wasm, mirroring the `put-deploy` session args.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstallArgs {
+    /// Prefix of the named keys the contract hash is stored under.
+    pub contract_name: String,
+    pub name: String,
+    /// Contract hash of the staked token formatted as `hash-...`, ignored by native pools.
+    pub address: String,
+    pub staking_starts: u64,
+    pub staking_ends: u64,
+    pub withdraw_starts: u64,
+    pub withdraw_ends: u64,
+    pub staking_total: U256,
+    pub min_stake: U256,
+    pub max_stake_per_address: U256,
+    pub max_stakers: u64,
+    /// `AssetKind` tag of the token interface, `3` for native CSPR.
+    pub asset_kind: u8,
+    /// `CallerPolicy` tag of the accepted stakers.
+    pub caller_policy: u8,
+}
+
+impl InstallArgs {
+    /// Session installing `wasm`, the compiled `cep47-token.wasm`.
+    pub fn session(&self, wasm: Vec<u8>) -> ExecutableDeployItem {
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: wasm,
+            args: runtime_args! {
+                "contract_name" => self.contract_name.clone(),
+                "name" => self.name.clone(),
+                "address" => self.address.clone(),
+                "staking_starts" => self.staking_starts,
+                "staking_ends" => self.staking_ends,
+                "withdraw_starts" => self.withdraw_starts,
+                "withdraw_ends" => self.withdraw_ends,
+                "staking_total" => self.staking_total,
+                "min_stake" => self.min_stake,
+                "max_stake_per_address" => self.max_stake_per_address,
+                "max_stakers" => self.max_stakers,
+                "asset_kind" => self.asset_kind,
+                "caller_policy" => self.caller_policy
+            },
+        }
+    }
+}
+
+/// Session running `wasm`, the compiled `approve-and-stake.wasm`, which approves the pool's package
+/// on its token for the part of `amount` under the cap and stakes it in the same deploy.
+pub fn approve_and_stake(
+    wasm: Vec<u8>,
+    pool_package: ContractPackageHash,
+    amount: U256,
+    proof: Vec<[u8; 32]>,
+    allocation: U256,
+) -> ExecutableDeployItem {
+    ExecutableDeployItem::ModuleBytes {
+        module_bytes: wasm,
+        args: runtime_args! {
+            "pool_package" => pool_package,
+            "amount" => amount,
+            "proof" => proof,
+            "allocation" => allocation
+        },
+    }
+}
+
+/// An installed pool, addressed by its contract hash.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pool {
+    contract_hash: ContractHash,
+}
+
+impl Pool {
+    pub fn new(contract_hash: ContractHash) -> Self {
+        Pool { contract_hash }
+    }
+
+    pub fn contract_hash(&self) -> ContractHash {
+        self.contract_hash
+    }
+
+    /// Session calling `entry_point` with `args`.
+    pub fn call(&self, entry_point: &str, args: RuntimeArgs) -> ExecutableDeployItem {
+        ExecutableDeployItem::StoredContractByHash {
+            hash: self.contract_hash,
+            entry_point: entry_point.to_string(),
+            args,
+        }
+    }
+
+    /// Session calling an entry point that takes no arguments, such as the getters `name`,
+    /// `staking_total` or `current_phase`.
+    pub fn getter(&self, entry_point: &str) -> ExecutableDeployItem {
+        self.call(entry_point, RuntimeArgs::new())
+    }
+
+    /// Stakes `amount`. Native pools take the motes from `purse`, token pools need `None`.
+    pub fn stake(
+        &self,
+        amount: U256,
+        proof: Vec<[u8; 32]>,
+        allocation: U256,
+        purse: Option<URef>,
+    ) -> ExecutableDeployItem {
+        let mut args = runtime_args! {
+            "amount" => amount,
+            "proof" => proof,
+            "allocation" => allocation
+        };
+        insert_purse(&mut args, purse);
+        self.call("stake", args)
+    }
+
+    pub fn withdraw(&self, amount: U256) -> ExecutableDeployItem {
+        self.call("withdraw", runtime_args! { "amount" => amount })
+    }
+
+    /// Funds rewards. Native pools take the motes from `purse`, token pools need `None`.
+    pub fn add_reward(
+        &self,
+        reward_amount: U256,
+        withdrawable_amount: U256,
+        purse: Option<URef>,
+    ) -> ExecutableDeployItem {
+        let mut args = runtime_args! {
+            "reward_amount" => reward_amount,
+            "withdrawable_amount" => withdrawable_amount
+        };
+        insert_purse(&mut args, purse);
+        self.call("add_reward", args)
+    }
+
+    pub fn claim_reward(&self) -> ExecutableDeployItem {
+        self.call("claim_reward", RuntimeArgs::new())
+    }
+
+    pub fn compound(&self, proof: Vec<[u8; 32]>, allocation: U256) -> ExecutableDeployItem {
+        self.call(
+            "compound",
+            runtime_args! { "proof" => proof, "allocation" => allocation },
+        )
+    }
+
+    pub fn finalize(&self) -> ExecutableDeployItem {
+        self.call("finalize", RuntimeArgs::new())
+    }
+
+    /// Pays the calling funder its share of the rewards left at `finalize`.
+    pub fn claim_refund(&self) -> ExecutableDeployItem {
+        self.call("claim_refund", RuntimeArgs::new())
+    }
+
+    pub fn amount_staked(&self, staker: Key) -> ExecutableDeployItem {
+        self.call("amount_staked", runtime_args! { "staker" => staker })
+    }
+
+    pub fn staker_info(&self, staker: Key) -> ExecutableDeployItem {
+        self.call("staker_info", runtime_args! { "staker" => staker })
+    }
+
+    pub fn preview_stake(&self, amount: U256) -> ExecutableDeployItem {
+        self.call("preview_stake", runtime_args! { "amount" => amount })
+    }
+
+    pub fn preview_withdraw(&self, staker: Key, amount: U256) -> ExecutableDeployItem {
+        self.call(
+            "preview_withdraw",
+            runtime_args! { "staker" => staker, "amount" => amount },
+        )
+    }
+
+    pub fn stakers(&self, offset: u64, limit: u64) -> ExecutableDeployItem {
+        self.call(
+            "stakers",
+            runtime_args! { "offset" => offset, "limit" => limit },
+        )
+    }
+
+    pub fn staked_at(&self, staker: Key, timestamp: u64) -> ExecutableDeployItem {
+        self.call(
+            "staked_at",
+            runtime_args! { "staker" => staker, "timestamp" => timestamp },
+        )
+    }
+
+    pub fn total_staked_at(&self, timestamp: u64) -> ExecutableDeployItem {
+        self.call(
+            "total_staked_at",
+            runtime_args! { "timestamp" => timestamp },
+        )
+    }
+
+    pub fn is_allowed_contract(
+        &self,
+        contract_package_hash: ContractPackageHash,
+    ) -> ExecutableDeployItem {
+        self.call(
+            "is_allowed_contract",
+            runtime_args! { "contract_package_hash" => contract_package_hash },
+        )
+    }
+
+    pub fn set_stake_limits(
+        &self,
+        min_stake: U256,
+        max_stake_per_address: U256,
+        max_stakers: u64,
+    ) -> ExecutableDeployItem {
+        self.call(
+            "set_stake_limits",
+            runtime_args! {
+                "min_stake" => min_stake,
+                "max_stake_per_address" => max_stake_per_address,
+                "max_stakers" => max_stakers
+            },
+        )
+    }
+
+    pub fn set_penalty(
+        &self,
+        penalty_bps: u64,
+        decays: bool,
+        treasury: Option<Key>,
+    ) -> ExecutableDeployItem {
+        self.call(
+            "set_penalty",
+            runtime_args! {
+                "penalty_bps" => penalty_bps,
+                "decays" => decays,
+                "treasury" => treasury
+            },
+        )
+    }
+
+    pub fn set_paused(&self, paused: bool) -> ExecutableDeployItem {
+        self.call("set_paused", runtime_args! { "paused" => paused })
+    }
+
+    pub fn set_merkle_root(&self, merkle_root: Option<[u8; 32]>) -> ExecutableDeployItem {
+        self.call(
+            "set_merkle_root",
+            runtime_args! { "merkle_root" => merkle_root },
+        )
+    }
+
+    pub fn set_allowed_contract(
+        &self,
+        contract_package_hash: ContractPackageHash,
+        allowed: bool,
+    ) -> ExecutableDeployItem {
+        self.call(
+            "set_allowed_contract",
+            runtime_args! {
+                "contract_package_hash" => contract_package_hash,
+                "allowed" => allowed
+            },
+        )
+    }
+
+    pub fn recover_token(
+        &self,
+        token_hash: ContractHash,
+        recipient: Key,
+        amount: U256,
+    ) -> ExecutableDeployItem {
+        self.call(
+            "recover_token",
+            runtime_args! {
+                "token_hash" => token_hash,
+                "recipient" => recipient,
+                "amount" => amount
+            },
+        )
+    }
+}
+
+fn insert_purse(args: &mut RuntimeArgs, purse: Option<URef>) {
+    if let Some(purse) = purse {
+        args.insert("purse", purse)
+            .expect("a URef is always a valid CLValue");
+    }
+}
@@ -0,0 +1,90 @@
+//! Reward and penalty formulas of the staking pool.
+//!
+//! The crate depends on nothing but `casper-types`, so the contract and the off-chain tools share
+//! the same arithmetic and it can be tested without a wasm runtime.
+//!
+//! Every function returns `None` instead of overflowing. Amounts and rewards below 2^96 with
+//! millisecond timestamps never overflow, as the widest product is `elapsed * reward * amount`.
+#![no_std]
+
+use casper_types::U256;
+
+/// Denominator of rates expressed in basis points.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Reward for withdrawing `amount` out of `staked_total` at `now`, growing linearly from
+/// `staking_ends` to the `amount` share of `early_withdraw_reward` at `withdraw_ends`.
+pub fn early_withdraw_reward(
+    amount: U256,
+    staked_total: U256,
+    early_withdraw_reward: U256,
+    staking_ends: u64,
+    withdraw_ends: u64,
+    now: u64,
+) -> Option<U256> {
+    if staked_total.is_zero() || now <= staking_ends || withdraw_ends <= staking_ends {
+        return Some(U256::zero());
+    }
+    let elapsed = core::cmp::min(now, withdraw_ends) - staking_ends;
+    let denom = U256::from(withdraw_ends - staking_ends).checked_mul(staked_total)?;
+    let numerator = U256::from(elapsed)
+        .checked_mul(early_withdraw_reward)?
+        .checked_mul(amount)?;
+    Some(numerator / denom)
+}
+
+/// Reward for withdrawing `amount` out of `staked_balance` after close, a pro-rata share of what
+/// is left in `reward_balance` plus the `total_claimed` stakers already took out of it.
+pub fn after_close_reward(
+    amount: U256,
+    staked_balance: U256,
+    reward_balance: U256,
+    total_claimed: U256,
+) -> Option<U256> {
+    if staked_balance.is_zero() {
+        return Some(U256::zero());
+    }
+    let numerator = reward_balance
+        .checked_add(total_claimed)?
+        .checked_mul(amount)?;
+    Some(numerator / staked_balance)
+}
+
+/// Penalty on withdrawing `amount` at `now`, `penalty_bps` of it. A decaying penalty shrinks
+/// linearly from the full rate at `withdraw_starts` to nothing at `withdraw_ends`.
+pub fn early_exit_penalty(
+    amount: U256,
+    penalty_bps: u64,
+    decays: bool,
+    withdraw_starts: u64,
+    withdraw_ends: u64,
+    now: u64,
+) -> Option<U256> {
+    let penalty = amount.checked_mul(U256::from(penalty_bps))? / BPS_DENOMINATOR;
+    if !decays {
+        return Some(penalty);
+    }
+    if now >= withdraw_ends {
+        return Some(U256::zero());
+    }
+    let remaining = withdraw_ends - core::cmp::max(now, withdraw_starts);
+    let penalty = penalty.checked_mul(U256::from(remaining))?;
+    Some(penalty / (withdraw_ends - withdraw_starts))
+}
+
+/// Part of `booked`, recorded against a stake of `staked`, attributable to `amount` of it. Used
+/// for the claimed rewards and compounded principal settled by a partial withdrawal.
+pub fn pro_rata(booked: U256, amount: U256, staked: U256) -> Option<U256> {
+    if staked.is_zero() {
+        return Some(U256::zero());
+    }
+    Some(booked.checked_mul(amount)? / staked)
+}
+
+/// Splits a requested stake into the part accepted under `staking_total` given the
+/// `staked_total` so far, and the refund that stays with the staker.
+pub fn clip_to_cap(amount: U256, staked_total: U256, staking_total: U256) -> (U256, U256) {
+    let remaining = staking_total.saturating_sub(staked_total);
+    let accepted = core::cmp::min(amount, remaining);
+    (accepted, amount - accepted)
+}